@@ -4,6 +4,10 @@
 //!
 //! The results of `borrowed_mownstr` should therefore be compared to `refs`,
 //! and that of `owned_mownstr` should be compared to `strings`.
+//!
+//! Similarly, `roundtrip_mownstr` (converting a `String` built with `push_str` to a `MownStr`,
+//! then back to a `String`) should be compared to `roundtrip_boxed`
+//! (doing the same through a `Box<str>`, which shrinks the `String`).
 
 use std::borrow::Cow;
 
@@ -315,6 +319,52 @@ fn substr_owned_cowstr(c: &mut Criterion) {
     );
 }
 
+/// Build a `String` with `push_str`, which usually leaves it with some spare capacity.
+fn pushed(txt: &str) -> String {
+    let mut ret = String::new();
+    for word in txt.split(' ') {
+        ret.push_str(word);
+        ret.push(' ');
+    }
+    ret
+}
+
+fn roundtrip_boxed(c: &mut Criterion) {
+    c.bench_with_input(
+        BenchmarkId::new("roundtrip_boxed", ""),
+        black_box(&STRINGS),
+        |b, &i| {
+            b.iter(|| {
+                let v = i
+                    .iter()
+                    .map(|r| pushed(r))
+                    .map(String::into_boxed_str)
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                assert!(v.len() == i.len());
+            });
+        },
+    );
+}
+
+fn roundtrip_mownstr(c: &mut Criterion) {
+    c.bench_with_input(
+        BenchmarkId::new("roundtrip_mownstr", ""),
+        black_box(&STRINGS),
+        |b, &i| {
+            b.iter(|| {
+                let v = i
+                    .iter()
+                    .map(|r| pushed(r))
+                    .map(MownStr::from)
+                    .map(String::from)
+                    .collect::<Vec<_>>();
+                assert!(v.len() == i.len());
+            });
+        },
+    );
+}
+
 criterion_group!(
     benches,
     refs,
@@ -334,7 +384,9 @@ criterion_group!(
     substr_borrowed_cowstr,
     substr_strings,
     substr_owned_mownstr,
-    substr_owned_cowstr
+    substr_owned_cowstr,
+    roundtrip_boxed,
+    roundtrip_mownstr
 );
 criterion_main!(benches);
 
@@ -0,0 +1,310 @@
+//! Derive macros for the [`IntoOwned`] and [`Reborrow`] traits of
+//! [`mownstr`](https://docs.rs/mownstr).
+//!
+//! These macros are re-exported by `mownstr` when its `derive` feature is enabled.
+//! They implement the traits by recursing through the fields of a struct or an enum,
+//! so that all its fields must implement them
+//! (`mownstr` implements them for `MownStr`, `Option`, `Vec`, `Box`, tuples, maps...).
+//!
+//! Every lifetime parameter of the type is replaced
+//! by `'static` in [`IntoOwned::Owned`],
+//! and by the lifetime of the borrow in [`Reborrow::Borrowed`].
+//! Every type parameter `T` is required to implement the derived trait,
+//! and is replaced by `T::Owned` or `T::Borrowed`, respectively.
+//! The predicates of the `where` clause of the type are also required
+//! to hold after these replacements.
+//!
+//! The generated code refers to the traits through the `::mownstr` path.
+//! If the `mownstr` crate is renamed (or re-exported by another crate),
+//! its path must be given with the `#[mownstr(crate = "...")]` attribute:
+//! ```
+//! # use mownstr as my_mownstr;
+//! # use mownstr::{IntoOwned, MownStr, Reborrow};
+//! #[derive(IntoOwned, Reborrow)]
+//! #[mownstr(crate = "my_mownstr")]
+//! struct Name<'a>(MownStr<'a>);
+//! ```
+//!
+//! # Usage
+//! ```
+//! # use mownstr::{IntoOwned, MownStr, Reborrow};
+//! #[derive(IntoOwned, Reborrow)]
+//! struct Element<'a> {
+//!     name: MownStr<'a>,
+//!     attributes: Vec<(MownStr<'a>, MownStr<'a>)>,
+//!     text: Option<MownStr<'a>>,
+//! }
+//!
+//! let txt = "<p>".to_string();
+//! let elt = Element {
+//!     name: MownStr::from(&txt[1..2]),
+//!     attributes: vec![],
+//!     text: None,
+//! };
+//! let elt: Element<'static> = elt.into_owned();
+//! drop(txt);
+//! assert!(elt.name.is_owned());
+//! assert!(elt.borrowed().name.is_borrowed());
+//! ```
+//!
+//! [`IntoOwned`]: https://docs.rs/mownstr/latest/mownstr/trait.IntoOwned.html
+//! [`IntoOwned::Owned`]: https://docs.rs/mownstr/latest/mownstr/trait.IntoOwned.html#associatedtype.Owned
+//! [`Reborrow`]: https://docs.rs/mownstr/latest/mownstr/trait.Reborrow.html
+//! [`Reborrow::Borrowed`]: https://docs.rs/mownstr/latest/mownstr/trait.Reborrow.html#associatedtype.Borrowed
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::visit_mut::{self, VisitMut};
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Error, Fields, GenericArgument,
+    GenericParam, Lifetime, LitStr, Path, Result, Type, TypePath, WherePredicate,
+};
+
+/// Derive `mownstr::IntoOwned`.
+///
+/// See the [crate documentation](crate) for more details.
+#[proc_macro_derive(IntoOwned, attributes(mownstr))]
+pub fn derive_into_owned(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    into_owned(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derive `mownstr::Reborrow`.
+///
+/// See the [crate documentation](crate) for more details.
+#[proc_macro_derive(Reborrow, attributes(mownstr))]
+pub fn derive_reborrow(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    reborrow(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn into_owned(mut input: DeriveInput) -> Result<TokenStream2> {
+    let krate = crate_path(&input)?;
+    let name = input.ident.clone();
+    let owned_lt = parse_quote!('static);
+    let owned_ty = |t: &syn::Ident| parse_quote!(<#t as #krate::IntoOwned>::Owned);
+    let owned_args = substitute_generics(&input, &owned_lt, owned_ty);
+    let owned_preds = substitute_predicates(&input, &owned_lt, owned_ty);
+    let body = convert_body(
+        &input,
+        false,
+        |field| quote!(#krate::IntoOwned::into_owned(#field)),
+    )?;
+    add_bounds(&mut input, parse_quote!(#krate::IntoOwned));
+    input
+        .generics
+        .make_where_clause()
+        .predicates
+        .extend(owned_preds);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::IntoOwned for #name #ty_generics #where_clause {
+            type Owned = #name<#(#owned_args),*>;
+
+            fn into_owned(self) -> Self::Owned {
+                #body
+            }
+        }
+    })
+}
+
+fn reborrow(mut input: DeriveInput) -> Result<TokenStream2> {
+    let krate = crate_path(&input)?;
+    let name = input.ident.clone();
+    let lt = Lifetime::new("'__mownstr_b", Span::call_site());
+    let borrowed_ty = |t: &syn::Ident| parse_quote!(<#t as #krate::Reborrow<#lt>>::Borrowed);
+    let borrowed_args = substitute_generics(&input, &lt, borrowed_ty);
+    let borrowed_preds = substitute_predicates(&input, &lt, borrowed_ty);
+    let body = convert_body(
+        &input,
+        true,
+        |field| quote!(#krate::Reborrow::borrowed(#field)),
+    )?;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let self_ty = quote!(#name #ty_generics);
+    add_bounds(&mut input, parse_quote!(#krate::Reborrow<#lt>));
+    input
+        .generics
+        .make_where_clause()
+        .predicates
+        .extend(borrowed_preds);
+    // every lifetime of the type must outlive the borrow
+    for param in input.generics.lifetimes_mut() {
+        param.bounds.push(lt.clone());
+    }
+    input.generics.params.insert(0, parse_quote!(#lt));
+    let (impl_generics, _, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #krate::Reborrow<#lt> for #self_ty #where_clause {
+            type Borrowed = #name<#(#borrowed_args),*>;
+
+            fn borrowed(&#lt self) -> Self::Borrowed {
+                #body
+            }
+        }
+    })
+}
+
+/// The path of the `mownstr` crate,
+/// given by the `#[mownstr(crate = "...")]` attribute (defaults to `::mownstr`).
+fn crate_path(input: &DeriveInput) -> Result<Path> {
+    let mut path = parse_quote!(::mownstr);
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("mownstr")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                path = meta.value()?.parse::<LitStr>()?.parse()?;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported mownstr attribute"))
+            }
+        })?;
+    }
+    Ok(path)
+}
+
+/// The generic arguments of the target type,
+/// where every lifetime is replaced by `lt`, and every type parameter `T` by `ty(T)`.
+fn substitute_generics(
+    input: &DeriveInput,
+    lt: &Lifetime,
+    ty: impl Fn(&syn::Ident) -> syn::Type,
+) -> Vec<GenericArgument> {
+    input
+        .generics
+        .params
+        .iter()
+        .map(|param| match param {
+            GenericParam::Lifetime(_) => GenericArgument::Lifetime(lt.clone()),
+            GenericParam::Type(t) => GenericArgument::Type(ty(&t.ident)),
+            GenericParam::Const(c) => {
+                let ident = &c.ident;
+                GenericArgument::Const(parse_quote!(#ident))
+            }
+        })
+        .collect()
+}
+
+/// The predicates of the `where` clause of the target type,
+/// where every lifetime parameter is replaced by `lt`,
+/// and every type parameter `T` by `ty(T)`.
+fn substitute_predicates(
+    input: &DeriveInput,
+    lt: &Lifetime,
+    ty: impl Fn(&syn::Ident) -> syn::Type,
+) -> Vec<WherePredicate> {
+    let Some(where_clause) = &input.generics.where_clause else {
+        return vec![];
+    };
+    let mut subst = Substitution {
+        lifetimes: input.generics.lifetimes().map(|l| &l.lifetime).collect(),
+        types: input.generics.type_params().map(|t| &t.ident).collect(),
+        lt,
+        ty,
+    };
+    let mut preds: Vec<_> = where_clause.predicates.iter().cloned().collect();
+    for pred in &mut preds {
+        subst.visit_where_predicate_mut(pred);
+    }
+    preds
+}
+
+/// Replaces the lifetime and type parameters of a type (see [`substitute_predicates`]).
+struct Substitution<'i, F> {
+    lifetimes: Vec<&'i Lifetime>,
+    types: Vec<&'i syn::Ident>,
+    lt: &'i Lifetime,
+    ty: F,
+}
+
+impl<F: Fn(&syn::Ident) -> syn::Type> VisitMut for Substitution<'_, F> {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut Lifetime) {
+        if self.lifetimes.contains(&&*lifetime) {
+            *lifetime = self.lt.clone();
+        }
+    }
+
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        visit_mut::visit_type_mut(self, ty);
+        // NB: associated types of type parameters (e.g. `T::Item`) are left unchanged,
+        // as the trait declaring them is unknown
+        if let Type::Path(TypePath { qself: None, path }) = ty {
+            if let Some(ident) = path.get_ident() {
+                if self.types.contains(&ident) {
+                    *ty = (self.ty)(ident);
+                }
+            }
+        }
+    }
+}
+
+/// Require every type parameter to implement `bound`.
+fn add_bounds(input: &mut DeriveInput, bound: syn::TypeParamBound) {
+    for param in input.generics.type_params_mut() {
+        param.bounds.push(bound.clone());
+    }
+}
+
+/// A `match` expression converting `self` field by field, using `convert`.
+///
+/// `by_ref` indicates whether `self` is a reference or a value.
+fn convert_body(
+    input: &DeriveInput,
+    by_ref: bool,
+    convert: impl Fn(&syn::Ident) -> TokenStream2,
+) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let arms: Vec<TokenStream2> = match &input.data {
+        Data::Struct(data) => vec![convert_arm(quote!(#name), &data.fields, &convert)],
+        Data::Enum(data) => data
+            .variants
+            .iter()
+            .map(|variant| {
+                let v = &variant.ident;
+                convert_arm(quote!(#name::#v), &variant.fields, &convert)
+            })
+            .collect(),
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span,
+                "unions are not supported",
+            ))
+        }
+    };
+    if arms.is_empty() {
+        // NB: `match self {}` is not exhaustive when self is a reference
+        return Ok(if by_ref {
+            quote!(match *self {})
+        } else {
+            quote!(match self {})
+        });
+    }
+    Ok(quote! {
+        match self {
+            #(#arms)*
+        }
+    })
+}
+
+/// A `match` arm destructuring `path` and rebuilding it with converted fields.
+///
+/// NB: braced patterns and expressions work for all kinds of structs and variants
+/// (named, tuple or unit).
+fn convert_arm(
+    path: TokenStream2,
+    fields: &Fields,
+    convert: impl Fn(&syn::Ident) -> TokenStream2,
+) -> TokenStream2 {
+    let members: Vec<_> = fields.members().collect();
+    let vars: Vec<_> = (0..members.len())
+        .map(|i| format_ident!("__mownstr_f{}", i))
+        .collect();
+    let converted = vars.iter().map(convert);
+    quote! {
+        #path { #(#members: #vars),* } => #path { #(#members: #converted),* },
+    }
+}
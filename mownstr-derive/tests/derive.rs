@@ -0,0 +1,175 @@
+use mownstr::{IntoOwned, MownStr, Reborrow};
+use std::collections::BTreeMap;
+
+#[derive(Debug, PartialEq, IntoOwned, Reborrow)]
+struct Named<'a> {
+    name: MownStr<'a>,
+    aliases: Vec<MownStr<'a>>,
+    parent: Option<Box<Named<'a>>>,
+    depth: usize,
+}
+
+#[derive(Debug, PartialEq, IntoOwned, Reborrow)]
+struct Tuple<'a>(MownStr<'a>, (MownStr<'a>, u32));
+
+#[derive(Debug, PartialEq, IntoOwned, Reborrow)]
+struct Unit;
+
+#[derive(Debug, PartialEq, IntoOwned, Reborrow)]
+enum Term<'a> {
+    Iri(MownStr<'a>),
+    Literal {
+        value: MownStr<'a>,
+        lang: Option<MownStr<'a>>,
+    },
+    Blank,
+}
+
+#[derive(Debug, PartialEq, IntoOwned, Reborrow)]
+struct Generic<'a, T, const N: usize> {
+    label: MownStr<'a>,
+    items: [MownStr<'a>; N],
+    value: T,
+    map: BTreeMap<MownStr<'a>, T>,
+}
+
+#[derive(Debug, PartialEq, IntoOwned, Reborrow)]
+struct Bounded<'a, T>
+where
+    T: Clone + Ord,
+{
+    label: MownStr<'a>,
+    values: Vec<T>,
+}
+
+// only checks that the derived code compiles
+#[allow(dead_code)]
+#[derive(IntoOwned, Reborrow)]
+enum Never {}
+
+fn source() -> String {
+    "hello world and everyone".to_string()
+}
+
+#[test]
+fn named() {
+    let txt = source();
+    let root = Named {
+        name: MownStr::from(&txt[..5]),
+        aliases: vec![],
+        parent: None,
+        depth: 0,
+    };
+    let named = Named {
+        name: MownStr::from(&txt[6..11]),
+        aliases: txt.split(' ').map(MownStr::from).collect(),
+        parent: Some(Box::new(root)),
+        depth: 1,
+    };
+    let owned: Named<'static> = named.into_owned();
+    drop(txt);
+    assert!(owned.name.is_owned());
+    assert!(owned.aliases.iter().all(MownStr::is_owned));
+    assert!(owned.parent.as_ref().unwrap().name.is_owned());
+    assert_eq!(owned.name, "world");
+    assert_eq!(owned.depth, 1);
+
+    let borrowed = owned.borrowed();
+    assert_eq!(borrowed, owned);
+    assert!(borrowed.name.is_borrowed());
+    assert_eq!(borrowed.name.as_ptr(), owned.name.as_ptr());
+    assert!(borrowed.aliases.iter().all(MownStr::is_borrowed));
+    assert!(borrowed.parent.as_ref().unwrap().name.is_borrowed());
+}
+
+#[test]
+fn tuple_and_unit() {
+    let txt = source();
+    let tuple = Tuple(MownStr::from(&txt[..5]), (MownStr::from(&txt[6..]), 42));
+    let owned: Tuple<'static> = tuple.into_owned();
+    drop(txt);
+    assert!(owned.0.is_owned());
+    assert!((owned.1).0.is_owned());
+    assert_eq!(owned.borrowed(), owned);
+    assert!(owned.borrowed().0.is_borrowed());
+    assert_eq!(Unit.into_owned(), Unit);
+    assert_eq!(Unit.borrowed(), Unit);
+}
+
+#[test]
+fn enumeration() {
+    let txt = source();
+    let terms = vec![
+        Term::Iri(MownStr::from(&txt[..5])),
+        Term::Literal {
+            value: MownStr::from(&txt[6..11]),
+            lang: Some(MownStr::from("en")),
+        },
+        Term::Blank,
+    ];
+    let owned: Vec<Term<'static>> = terms.into_owned();
+    drop(txt);
+    assert!(matches!(&owned[0], Term::Iri(iri) if iri.is_owned()));
+    assert!(
+        matches!(&owned[1], Term::Literal { value, lang: Some(lang) } if value.is_owned() && lang.is_owned())
+    );
+    assert_eq!(owned[2], Term::Blank);
+    let borrowed = owned.borrowed();
+    assert_eq!(borrowed, owned);
+    assert!(matches!(&borrowed[1], Term::Literal { value, .. } if value.is_borrowed()));
+}
+
+#[test]
+fn generic() {
+    let txt = source();
+    let mut map = BTreeMap::new();
+    map.insert(MownStr::from(&txt[..5]), MownStr::from(&txt[6..]));
+    let generic: Generic<'_, MownStr, 1> = Generic {
+        label: MownStr::from(&txt[..5]),
+        items: [MownStr::from(&txt[..])],
+        value: MownStr::from(&txt[6..11]),
+        map,
+    };
+    let owned: Generic<'static, MownStr<'static>, 1> = generic.into_owned();
+    drop(txt);
+    assert!(owned.value.is_owned());
+    assert!(owned.items[0].is_owned());
+    assert!(owned.map.keys().all(MownStr::is_owned));
+    let borrowed = owned.borrowed();
+    assert!(borrowed.value.is_borrowed());
+    assert_eq!(borrowed.map.values().next().unwrap(), "world and everyone");
+}
+
+#[test]
+fn where_clause() {
+    let txt = source();
+    let bounded = Bounded {
+        label: MownStr::from(&txt[..5]),
+        values: vec![MownStr::from(&txt[6..11])],
+    };
+    let owned: Bounded<'static, MownStr<'static>> = bounded.into_owned();
+    drop(txt);
+    assert!(owned.label.is_owned());
+    assert!(owned.values[0].is_owned());
+    let borrowed = owned.borrowed();
+    assert_eq!(borrowed, owned);
+    assert!(borrowed.values[0].is_borrowed());
+}
+
+mod renamed {
+    pub use mownstr as other_name;
+}
+
+#[derive(Debug, PartialEq, IntoOwned, Reborrow)]
+#[mownstr(crate = "renamed::other_name")]
+struct Renamed<'a>(MownStr<'a>);
+
+#[test]
+fn renamed_crate() {
+    let txt = source();
+    let renamed = Renamed(MownStr::from(&txt[..5]));
+    let owned: Renamed<'static> = renamed.into_owned();
+    drop(txt);
+    assert!(owned.0.is_owned());
+    assert!(owned.borrowed().0.is_borrowed());
+}
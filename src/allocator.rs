@@ -0,0 +1,232 @@
+//! Allocation of owned [`MownStr`] in a custom allocator,
+//! available with the feature `allocator-api2`.
+
+use super::{CustomHeader, MownStr, LEN_MASK};
+use allocator_api2::alloc::{AllocError, Allocator, Layout};
+use core::ptr::{self, NonNull};
+use core::sync::atomic::AtomicUsize;
+
+/// The layout of a custom string allocated with an allocator of type `A`:
+/// this struct, followed by the bytes of the string.
+#[repr(C)]
+struct InAllocator<A> {
+    header: CustomHeader,
+    alloc: A,
+}
+
+/// The layout of an `InAllocator<A>` followed by `len` bytes,
+/// and the offset of those bytes.
+fn layout_in<A>(len: usize) -> Result<(Layout, usize), AllocError> {
+    let bytes = Layout::array::<u8>(len).map_err(|_| AllocError)?;
+    Layout::new::<InAllocator<A>>()
+        .extend(bytes)
+        .map_err(|_| AllocError)
+}
+
+unsafe fn drop_in<A: Allocator>(header: NonNull<CustomHeader>, len: usize) {
+    // SAFETY: the same layout was successfully computed at allocation time
+    let (layout, _) = layout_in::<A>(len).unwrap_unchecked();
+    let inner = header.cast::<InAllocator<A>>();
+    let alloc = ptr::read(ptr::addr_of!((*inner.as_ptr()).alloc));
+    alloc.deallocate(inner.cast(), layout);
+}
+
+impl MownStr<'_> {
+    /// Build an owned `MownStr` by copying `other` in the allocator `alloc`.
+    ///
+    /// The resulting `MownStr` is [shared](MownStr::is_shared):
+    /// cloning it does not copy the string,
+    /// which is freed (through `alloc`) when the last clone is dropped.
+    ///
+    /// As any `MownStr`, the result is `Send` and `Sync`, and can be converted to
+    /// a `MownStr<'static>` without copying the string ([`into_owned`](MownStr::into_owned)).
+    /// This is why `A` must be `Send`, `Sync` and `'static`:
+    /// to use a region (or arena) allocator, `alloc` must therefore be
+    /// an owned handle on the region (e.g. wrapped in an `Arc`) rather than a reference to it,
+    /// and the region is kept alive as long as the string.
+    ///
+    /// Note that [mutating](MownStr::make_mut) the resulting `MownStr`
+    /// moves its content to the global allocator.
+    ///
+    /// # Panic
+    /// On allocation failure, this calls [`handle_alloc_error`](alloc::alloc::handle_alloc_error).
+    /// See [`try_new_in`](MownStr::try_new_in) for a fallible version.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// use allocator_api2::alloc::Global;
+    /// let ms = MownStr::new_in("hello", Global);
+    /// assert!(ms.is_owned());
+    /// assert_eq!(ms, "hello");
+    /// ```
+    pub fn new_in<A>(other: &str, alloc: A) -> Self
+    where
+        A: Allocator + Send + Sync + 'static,
+    {
+        match Self::try_new_in(other, alloc) {
+            Ok(ms) => ms,
+            Err(_) => match layout_in::<A>(other.len()) {
+                Ok((layout, _)) => alloc::alloc::handle_alloc_error(layout),
+                Err(_) => panic!("string too long to be allocated"),
+            },
+        }
+    }
+
+    /// Build an owned `MownStr` by copying `other` in the allocator `alloc`,
+    /// or fail if the allocation fails.
+    ///
+    /// See [`new_in`](MownStr::new_in) for more details.
+    pub fn try_new_in<A>(other: &str, alloc: A) -> Result<Self, AllocError>
+    where
+        A: Allocator + Send + Sync + 'static,
+    {
+        let len = other.len();
+        if len > LEN_MASK {
+            return Err(AllocError);
+        }
+        let (layout, offset) = layout_in::<A>(len)?;
+        let inner = alloc.allocate(layout)?.cast::<InAllocator<A>>();
+        unsafe {
+            // SAFETY: inner points to a newly allocated block, of the appropriate layout
+            let data = inner.as_ptr().cast::<u8>().add(offset);
+            ptr::copy_nonoverlapping(other.as_ptr(), data, len);
+            inner.as_ptr().write(InAllocator {
+                header: CustomHeader {
+                    count: AtomicUsize::new(1),
+                    data: NonNull::new_unchecked(data),
+                    drop: drop_in::<A>,
+                },
+                alloc,
+            });
+            Ok(MownStr::from_custom(inner.cast(), len))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::MownStr;
+    use allocator_api2::alloc::{AllocError, Allocator, Global, Layout};
+    use core::ptr::NonNull;
+    use core::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// An allocator counting the allocated blocks.
+    #[derive(Clone, Default)]
+    struct Counting(Arc<AtomicUsize>);
+
+    impl Counting {
+        fn count(&self) -> usize {
+            self.0.load(Ordering::SeqCst)
+        }
+    }
+
+    unsafe impl Allocator for Counting {
+        fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Global.allocate(layout)
+        }
+
+        unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+            self.0.fetch_sub(1, Ordering::SeqCst);
+            Global.deallocate(ptr, layout);
+        }
+    }
+
+    /// An allocator that always fails.
+    struct Failing;
+
+    unsafe impl Allocator for Failing {
+        fn allocate(&self, _: Layout) -> Result<NonNull<[u8]>, AllocError> {
+            Err(AllocError)
+        }
+
+        unsafe fn deallocate(&self, _: NonNull<u8>, _: Layout) {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn new_in() {
+        let alloc = Counting::default();
+        let mown = MownStr::new_in("hello", alloc.clone());
+        assert_eq!(alloc.count(), 1);
+        assert!(mown.is_owned());
+        assert!(mown.is_shared());
+        assert_eq!(mown, "hello");
+        drop(mown);
+        assert_eq!(alloc.count(), 0);
+    }
+
+    #[test]
+    fn new_in_empty() {
+        let alloc = Counting::default();
+        let mown = MownStr::new_in("", alloc.clone());
+        assert_eq!(mown, "");
+        drop(mown);
+        assert_eq!(alloc.count(), 0);
+    }
+
+    #[test]
+    fn clone_in() {
+        let alloc = Counting::default();
+        let mown1 = MownStr::new_in("hello", alloc.clone());
+        let mown2 = mown1.clone();
+        assert_eq!(alloc.count(), 1);
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+        drop(mown1);
+        assert_eq!(alloc.count(), 1);
+        assert_eq!(mown2, "hello");
+        drop(mown2);
+        assert_eq!(alloc.count(), 0);
+    }
+
+    #[test]
+    fn borrowed_in() {
+        let mown1 = MownStr::new_in("hello", Global);
+        let mown2 = mown1.borrowed();
+        assert!(mown2.is_borrowed());
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+    }
+
+    #[test]
+    fn to_in() {
+        let alloc = Counting::default();
+        let mown = MownStr::new_in("hello", alloc.clone());
+        assert_eq!(mown.clone().to::<String>(), "hello");
+        assert_eq!(&*std::sync::Arc::<str>::from(mown.clone()), "hello");
+        let mown2 = mown.into_owned();
+        assert_eq!(alloc.count(), 1);
+        assert_eq!(mown2, "hello");
+        drop(mown2);
+        assert_eq!(alloc.count(), 0);
+    }
+
+    #[test]
+    fn make_mut_in() {
+        // make_mut copies the string out of the custom allocator
+        let alloc = Counting::default();
+        let mut mown = MownStr::new_in("hello", alloc.clone());
+        mown.make_mut().make_ascii_uppercase();
+        assert!(!mown.is_shared());
+        assert_eq!(mown, "HELLO");
+        assert_eq!(alloc.count(), 0);
+    }
+
+    #[test]
+    fn try_new_in() {
+        assert!(MownStr::try_new_in("hello", Failing).is_err());
+        assert!(MownStr::try_new_in("hello", Global).is_ok());
+    }
+
+    #[test]
+    fn send_in() {
+        let alloc = Counting::default();
+        let mown = MownStr::new_in("hello", alloc.clone());
+        std::thread::spawn(move || assert_eq!(mown, "hello"))
+            .join()
+            .unwrap();
+        assert_eq!(alloc.count(), 0);
+    }
+}
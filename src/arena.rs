@@ -0,0 +1,290 @@
+//! Arena-backed allocation of [`MownStr`].
+
+use super::MownStr;
+use alloc::alloc::{alloc, dealloc, handle_alloc_error, Layout};
+use alloc::vec::Vec;
+use core::cell::RefCell;
+use core::ptr::{self, NonNull};
+use core::slice;
+use core::str;
+
+const DEFAULT_CHUNK_SIZE: usize = 4096;
+
+/// An arena, in which strings can be copied,
+/// producing [borrowed](MownStr::is_borrowed) `MownStr`s.
+///
+/// Strings are copied in large chunks of memory,
+/// which are all freed at once when the arena is [reset](MownArena::reset) or dropped.
+/// Compared to owned `MownStr`s,
+/// this saves one allocation and one deallocation per string.
+///
+/// # Usage
+/// ```
+/// # use mownstr::MownArena;
+/// let arena = MownArena::new();
+/// let ms = arena.alloc(&"hello".to_string());
+/// assert!(ms.is_borrowed());
+/// assert_eq!(arena.stats().bytes_used, 5);
+/// ```
+pub struct MownArena {
+    chunk_size: usize,
+    inner: RefCell<Inner>,
+}
+
+// MownArena does not implement `Send` by default,
+// because NonNull<u8> does not.
+// However, it is safe to declare it as Send,
+// because it exclusively owns its chunks.
+unsafe impl Send for MownArena {}
+
+/// Statistics about the memory used by a [`MownArena`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ArenaStats {
+    /// Number of chunks currently allocated by the arena.
+    pub chunks: usize,
+    /// Total size, in bytes, of the chunks currently allocated by the arena.
+    pub bytes_allocated: usize,
+    /// Total length, in bytes, of the strings copied in the arena.
+    pub bytes_used: usize,
+}
+
+#[derive(Default)]
+struct Inner {
+    // NB: the last chunk is the one being filled
+    chunks: Vec<Chunk>,
+    // number of bytes used in the last chunk
+    pos: usize,
+    bytes_used: usize,
+}
+
+struct Chunk {
+    ptr: NonNull<u8>,
+    cap: usize,
+}
+
+impl Chunk {
+    fn new(cap: usize) -> Self {
+        debug_assert!(cap > 0);
+        let layout = Layout::array::<u8>(cap).unwrap();
+        let ptr = unsafe { alloc(layout) };
+        let ptr = NonNull::new(ptr).unwrap_or_else(|| handle_alloc_error(layout));
+        Chunk { ptr, cap }
+    }
+}
+
+impl Drop for Chunk {
+    fn drop(&mut self) {
+        let layout = Layout::array::<u8>(self.cap).unwrap();
+        unsafe { dealloc(self.ptr.as_ptr(), layout) }
+    }
+}
+
+impl MownArena {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_chunk_size(DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Build an arena allocating chunks of (at least) `chunk_size` bytes.
+    ///
+    /// Strings longer than `chunk_size` are allocated in their own chunk.
+    ///
+    /// # Panic
+    /// Panics if `chunk_size` is 0.
+    #[must_use]
+    pub fn with_chunk_size(chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        MownArena {
+            chunk_size,
+            inner: RefCell::default(),
+        }
+    }
+
+    /// Copy `other` in this arena,
+    /// and return a `MownStr` borrowed from that copy.
+    pub fn alloc(&self, other: &str) -> MownStr<'_> {
+        let len = other.len();
+        if len == 0 {
+            return MownStr::from_ref("");
+        }
+        let mut inner = self.inner.borrow_mut();
+        let dst = if len > self.chunk_size {
+            // dedicated chunk, inserted before the one being filled (if any)
+            let chunk = Chunk::new(len);
+            let dst = chunk.ptr.as_ptr();
+            if inner.chunks.is_empty() {
+                inner.chunks.push(chunk);
+                inner.pos = len;
+            } else {
+                let last = inner.chunks.len() - 1;
+                inner.chunks.insert(last, chunk);
+            }
+            dst
+        } else {
+            let fits = matches!(inner.chunks.last(), Some(c) if c.cap - inner.pos >= len);
+            if !fits {
+                inner.chunks.push(Chunk::new(self.chunk_size));
+                inner.pos = 0;
+            }
+            let pos = inner.pos;
+            inner.pos += len;
+            let chunk = inner.chunks.last().unwrap();
+            // SAFETY: the chunk has at least pos+len bytes
+            unsafe { chunk.ptr.as_ptr().add(pos) }
+        };
+        inner.bytes_used += len;
+        // SAFETY: dst points to len bytes, that no other MownStr is using;
+        // they are not modified until the arena is reset or dropped,
+        // which requires that all the returned MownStr are dropped
+        unsafe {
+            ptr::copy_nonoverlapping(other.as_ptr(), dst, len);
+            let txt = str::from_utf8_unchecked(slice::from_raw_parts(dst, len));
+            MownStr::from_ref(txt)
+        }
+    }
+
+    /// Free all the strings copied in this arena.
+    ///
+    /// One chunk is kept (if any), to be reused by subsequent allocations;
+    /// dedicated chunks (holding strings longer than the chunk size) are always freed.
+    pub fn reset(&mut self) {
+        let inner = self.inner.get_mut();
+        let kept = inner
+            .chunks
+            .iter()
+            .rposition(|c| c.cap == self.chunk_size)
+            .map(|i| inner.chunks.swap_remove(i));
+        inner.chunks.clear();
+        inner.chunks.extend(kept);
+        inner.pos = 0;
+        inner.bytes_used = 0;
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> ArenaStats {
+        let inner = self.inner.borrow();
+        ArenaStats {
+            chunks: inner.chunks.len(),
+            bytes_allocated: inner.chunks.iter().map(|c| c.cap).sum(),
+            bytes_used: inner.bytes_used,
+        }
+    }
+}
+
+impl Default for MownArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn alloc() {
+        let arena = MownArena::new();
+        let txt = "hello".to_string();
+        let mown1 = arena.alloc(&txt);
+        let mown2 = arena.alloc(&txt);
+        drop(txt);
+        assert!(mown1.is_borrowed());
+        assert_eq!(mown1, "hello");
+        assert_eq!(mown2, "hello");
+        assert_ne!(mown1.as_ptr(), mown2.as_ptr());
+    }
+
+    #[test]
+    fn alloc_empty() {
+        let arena = MownArena::new();
+        let mown = arena.alloc("");
+        assert_eq!(mown, "");
+        assert_eq!(arena.stats(), ArenaStats::default());
+    }
+
+    #[test]
+    fn alloc_many_chunks() {
+        let arena = MownArena::with_chunk_size(16);
+        let v: Vec<_> = (0..100)
+            .map(|i| arena.alloc(&format!("string #{i}")))
+            .collect();
+        for (i, mown) in v.iter().enumerate() {
+            assert_eq!(&mown[..], format!("string #{i}"));
+        }
+        let stats = arena.stats();
+        assert!(stats.chunks > 1);
+        assert_eq!(stats.bytes_allocated, stats.chunks * 16);
+        assert_eq!(stats.bytes_used, v.iter().map(|m| m.len()).sum::<usize>());
+    }
+
+    #[test]
+    fn alloc_large() {
+        let arena = MownArena::with_chunk_size(16);
+        let mown1 = arena.alloc("hello");
+        let mown2 = arena.alloc("hello world and everyone");
+        let mown3 = arena.alloc("world");
+        assert_eq!(mown1, "hello");
+        assert_eq!(mown2, "hello world and everyone");
+        assert_eq!(mown3, "world");
+        assert_eq!(mown3.as_ptr(), mown1.as_ptr().wrapping_add(5));
+        assert_eq!(
+            arena.stats(),
+            ArenaStats {
+                chunks: 2,
+                bytes_allocated: 16 + 24,
+                bytes_used: 34,
+            }
+        );
+    }
+
+    #[test]
+    fn alloc_large_first() {
+        let arena = MownArena::with_chunk_size(16);
+        let mown1 = arena.alloc("hello world and everyone");
+        let mown2 = arena.alloc("hello");
+        assert_eq!(mown1, "hello world and everyone");
+        assert_eq!(mown2, "hello");
+        assert_eq!(arena.stats().chunks, 2);
+    }
+
+    #[test]
+    fn reset() {
+        let mut arena = MownArena::with_chunk_size(16);
+        for i in 0..100 {
+            let _ = arena.alloc(&format!("string #{i}"));
+        }
+        arena.reset();
+        assert_eq!(
+            arena.stats(),
+            ArenaStats {
+                chunks: 1,
+                bytes_allocated: 16,
+                bytes_used: 0,
+            }
+        );
+        let mown = arena.alloc("hello");
+        assert_eq!(mown, "hello");
+        assert_eq!(arena.stats().chunks, 1);
+    }
+
+    #[test]
+    fn reset_after_large() {
+        let mut arena = MownArena::with_chunk_size(16);
+        let _ = arena.alloc("hello world and everyone");
+        arena.reset();
+        assert_eq!(arena.stats(), ArenaStats::default());
+
+        let _ = arena.alloc("hello world and everyone");
+        let _ = arena.alloc("hello");
+        let _ = arena.alloc("hello world and everyone, again");
+        arena.reset();
+        assert_eq!(
+            arena.stats(),
+            ArenaStats {
+                chunks: 1,
+                bytes_allocated: 16,
+                bytes_used: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,434 @@
+//! Case-insensitive strings, for use as keys.
+//!
+//! * [`CaseInsensitive`] wraps a string-like value (typically a [`MownStr`]),
+//!   preserving its original spelling (and ownership),
+//!   but comparing and hashing it case-insensitively;
+//! * [`CaseInsensitiveStr`] is its borrowed counterpart,
+//!   allowing to look up `CaseInsensitive` keys with a `&str`.
+//!
+//! Case-insensitivity comes in two [flavours](Folding):
+//! [`Ascii`] (the default) and [`Unicode`].
+//!
+//! # Usage
+//! ```
+//! # use mownstr::MownStr;
+//! # use mownstr::case::{CaseInsensitive, CaseInsensitiveStr};
+//! # use std::collections::HashMap;
+//! let mut headers = HashMap::new();
+//! headers.insert(CaseInsensitive::new(MownStr::from("Content-Type")), "text/plain");
+//! assert_eq!(headers.get(CaseInsensitiveStr::new("content-type")), Some(&"text/plain"));
+//! let key = headers.keys().next().unwrap();
+//! assert_eq!(key.as_inner(), "Content-Type");
+//! ```
+//!
+//! [`MownStr`]: crate::MownStr
+
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+/// The flavours of case-insensitivity: [`Ascii`] and [`Unicode`].
+pub trait Folding: private::Sealed {
+    #[doc(hidden)]
+    fn eq(a: &str, b: &str) -> bool;
+    #[doc(hidden)]
+    fn cmp(a: &str, b: &str) -> Ordering;
+    #[doc(hidden)]
+    fn hash<H: Hasher>(txt: &str, state: &mut H);
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// ASCII case-insensitivity: non-ASCII characters are compared as is.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Ascii;
+
+impl private::Sealed for Ascii {}
+
+impl Folding for Ascii {
+    fn eq(a: &str, b: &str) -> bool {
+        a.eq_ignore_ascii_case(b)
+    }
+
+    fn cmp(a: &str, b: &str) -> Ordering {
+        let a = a.bytes().map(|b| b.to_ascii_lowercase());
+        let b = b.bytes().map(|b| b.to_ascii_lowercase());
+        a.cmp(b)
+    }
+
+    fn hash<H: Hasher>(txt: &str, state: &mut H) {
+        let mut buffer = [0; 32];
+        for chunk in txt.as_bytes().chunks(buffer.len()) {
+            let folded = &mut buffer[..chunk.len()];
+            folded.copy_from_slice(chunk);
+            folded.make_ascii_lowercase();
+            state.write(folded);
+        }
+        state.write_u8(0xff);
+    }
+}
+
+/// Unicode case-insensitivity.
+///
+/// Each character is folded by converting it to uppercase, then to lowercase
+/// (so that, e.g., "ß" matches "SS", and "ς" matches "σ").
+/// This matches the Unicode default case folding for almost all characters.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Unicode;
+
+impl Unicode {
+    fn fold(txt: &str) -> impl Iterator<Item = char> + '_ {
+        txt.chars()
+            .flat_map(char::to_uppercase)
+            .flat_map(char::to_lowercase)
+    }
+}
+
+impl private::Sealed for Unicode {}
+
+impl Folding for Unicode {
+    fn eq(a: &str, b: &str) -> bool {
+        a == b || Self::fold(a).eq(Self::fold(b))
+    }
+
+    fn cmp(a: &str, b: &str) -> Ordering {
+        Self::fold(a).cmp(Self::fold(b))
+    }
+
+    fn hash<H: Hasher>(txt: &str, state: &mut H) {
+        for c in Self::fold(txt) {
+            state.write_u32(c as u32);
+        }
+        state.write_u8(0xff);
+    }
+}
+
+/// A `str` that compares and hashes case-insensitively.
+///
+/// This is the borrowed counterpart of [`CaseInsensitive`],
+/// in the same way as `str` is the borrowed counterpart of `String`.
+#[repr(transparent)]
+pub struct CaseInsensitiveStr<F = Ascii> {
+    _folding: PhantomData<F>,
+    txt: str,
+}
+
+impl CaseInsensitiveStr {
+    /// Wrap `txt` as an ASCII case-insensitive string.
+    #[must_use]
+    pub fn new(txt: &str) -> &Self {
+        Self::wrap(txt)
+    }
+}
+
+impl CaseInsensitiveStr<Unicode> {
+    /// Wrap `txt` as a Unicode case-insensitive string.
+    #[must_use]
+    pub fn unicode(txt: &str) -> &Self {
+        Self::wrap(txt)
+    }
+}
+
+impl<F: Folding> CaseInsensitiveStr<F> {
+    fn wrap(txt: &str) -> &Self {
+        // SAFETY: CaseInsensitiveStr is a transparent wrapper around str
+        unsafe { &*(txt as *const str as *const Self) }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.txt
+    }
+}
+
+impl<F: Folding> Hash for CaseInsensitiveStr<F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        F::hash(&self.txt, state);
+    }
+}
+
+impl<F: Folding> PartialEq for CaseInsensitiveStr<F> {
+    fn eq(&self, other: &Self) -> bool {
+        F::eq(&self.txt, &other.txt)
+    }
+}
+
+impl<F: Folding> Eq for CaseInsensitiveStr<F> {}
+
+impl<F: Folding> PartialOrd for CaseInsensitiveStr<F> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<F: Folding> Ord for CaseInsensitiveStr<F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        F::cmp(&self.txt, &other.txt)
+    }
+}
+
+impl<F> fmt::Debug for CaseInsensitiveStr<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.txt, f)
+    }
+}
+
+impl<F> fmt::Display for CaseInsensitiveStr<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.txt, f)
+    }
+}
+
+/// A string-like value (typically a [`MownStr`](crate::MownStr))
+/// that compares and hashes case-insensitively, according to `F`.
+///
+/// The wrapped value is kept unchanged,
+/// so its original spelling (and, for a `MownStr`, its ownership) is preserved.
+/// It can be [borrowed](Borrow) as a [`CaseInsensitiveStr`],
+/// which allows to look it up in maps and sets with a `&str`.
+///
+/// # Usage
+/// ```
+/// # use mownstr::MownStr;
+/// # use mownstr::case::CaseInsensitive;
+/// let a = CaseInsensitive::unicode(MownStr::from("Straße"));
+/// let b = CaseInsensitive::unicode(MownStr::from("STRASSE"));
+/// assert_eq!(a, b);
+/// assert!(a.as_inner().is_borrowed());
+/// ```
+#[derive(Clone, Copy, Default)]
+pub struct CaseInsensitive<T, F = Ascii> {
+    inner: T,
+    _folding: PhantomData<F>,
+}
+
+impl<T: AsRef<str>> CaseInsensitive<T> {
+    /// Wrap `inner` as an ASCII case-insensitive string.
+    #[must_use]
+    pub fn new(inner: T) -> Self {
+        Self::wrap(inner)
+    }
+}
+
+impl<T: AsRef<str>> CaseInsensitive<T, Unicode> {
+    /// Wrap `inner` as a Unicode case-insensitive string.
+    #[must_use]
+    pub fn unicode(inner: T) -> Self {
+        Self::wrap(inner)
+    }
+}
+
+impl<T: AsRef<str>, F: Folding> CaseInsensitive<T, F> {
+    fn wrap(inner: T) -> Self {
+        CaseInsensitive {
+            inner,
+            _folding: PhantomData,
+        }
+    }
+
+    #[must_use]
+    pub fn as_inner(&self) -> &T {
+        &self.inner
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    #[must_use]
+    pub fn as_case_insensitive_str(&self) -> &CaseInsensitiveStr<F> {
+        CaseInsensitiveStr::wrap(self.inner.as_ref())
+    }
+}
+
+impl<T: AsRef<str>, F: Folding> From<T> for CaseInsensitive<T, F> {
+    fn from(other: T) -> Self {
+        Self::wrap(other)
+    }
+}
+
+impl<T: AsRef<str>, F: Folding> Deref for CaseInsensitive<T, F> {
+    type Target = CaseInsensitiveStr<F>;
+
+    fn deref(&self) -> &CaseInsensitiveStr<F> {
+        self.as_case_insensitive_str()
+    }
+}
+
+impl<T: AsRef<str>, F: Folding> AsRef<str> for CaseInsensitive<T, F> {
+    fn as_ref(&self) -> &str {
+        self.inner.as_ref()
+    }
+}
+
+impl<T: AsRef<str>, F: Folding> Borrow<CaseInsensitiveStr<F>> for CaseInsensitive<T, F> {
+    fn borrow(&self) -> &CaseInsensitiveStr<F> {
+        self.as_case_insensitive_str()
+    }
+}
+
+impl<T: AsRef<str>, F: Folding> Hash for CaseInsensitive<T, F> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_case_insensitive_str().hash(state);
+    }
+}
+
+impl<T: AsRef<str>, U: AsRef<str>, F: Folding> PartialEq<CaseInsensitive<U, F>>
+    for CaseInsensitive<T, F>
+{
+    fn eq(&self, other: &CaseInsensitive<U, F>) -> bool {
+        F::eq(self.inner.as_ref(), other.inner.as_ref())
+    }
+}
+
+impl<T: AsRef<str>, F: Folding> Eq for CaseInsensitive<T, F> {}
+
+impl<T: AsRef<str>, F: Folding> PartialEq<str> for CaseInsensitive<T, F> {
+    fn eq(&self, other: &str) -> bool {
+        F::eq(self.inner.as_ref(), other)
+    }
+}
+
+impl<'b, T: AsRef<str>, F: Folding> PartialEq<&'b str> for CaseInsensitive<T, F> {
+    fn eq(&self, other: &&'b str) -> bool {
+        F::eq(self.inner.as_ref(), other)
+    }
+}
+
+impl<T: AsRef<str>, U: AsRef<str>, F: Folding> PartialOrd<CaseInsensitive<U, F>>
+    for CaseInsensitive<T, F>
+{
+    fn partial_cmp(&self, other: &CaseInsensitive<U, F>) -> Option<Ordering> {
+        Some(F::cmp(self.inner.as_ref(), other.inner.as_ref()))
+    }
+}
+
+impl<T: AsRef<str>, F: Folding> Ord for CaseInsensitive<T, F> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        F::cmp(self.inner.as_ref(), other.inner.as_ref())
+    }
+}
+
+impl<T: fmt::Debug, F> fmt::Debug for CaseInsensitive<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T: fmt::Display, F> fmt::Display for CaseInsensitive<T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MownStr;
+    use alloc::string::{String, ToString};
+    use alloc::vec::Vec;
+    use std::collections::hash_map::DefaultHasher;
+    use std::collections::{BTreeSet, HashMap};
+
+    fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    type Ci<'a> = CaseInsensitive<MownStr<'a>>;
+    type Uci<'a> = CaseInsensitive<MownStr<'a>, Unicode>;
+
+    #[test]
+    fn ascii() {
+        let a = Ci::new("Content-Type".into());
+        let b = Ci::new("content-type".to_string().into());
+        assert_eq!(a, b);
+        assert_eq!(a, "CONTENT-TYPE");
+        assert_ne!(a, "Content-Length");
+        assert!(a.as_inner().is_borrowed());
+        assert_eq!(a.as_inner(), "Content-Type");
+        assert_eq!(a.to_string(), "Content-Type");
+        assert_eq!(format!("{a:?}"), "\"Content-Type\"");
+        // non-ASCII characters are not folded
+        assert_ne!(Ci::new("été".into()), Ci::new("ÉTÉ".into()));
+    }
+
+    #[test]
+    fn unicode() {
+        assert_eq!(Uci::unicode("été".into()), Uci::unicode("ÉTÉ".into()));
+        assert_eq!(
+            Uci::unicode("Straße".into()),
+            Uci::unicode("STRASSE".into())
+        );
+        assert_eq!(
+            Uci::unicode("ΣΊΣΥΦΟΣ".into()),
+            Uci::unicode("σίσυφος".into())
+        );
+        assert_ne!(Uci::unicode("été".into()), Uci::unicode("ete".into()));
+    }
+
+    #[test]
+    fn ascii_hash() {
+        let long = "Hello World And Everyone, This Is Longer Than 32 Bytes";
+        for (a, b) in [
+            ("Content-Type", "CONTENT-TYPE"),
+            (long, &long.to_lowercase()),
+            ("", ""),
+        ] {
+            let h1 = hash_of(&Ci::new(a.into()));
+            let h2 = hash_of(CaseInsensitiveStr::new(b));
+            assert_eq!(h1, h2);
+        }
+        // make sure that there is no collision between concatenations
+        assert_ne!(
+            hash_of(&(Ci::new("ab".into()), Ci::new("c".into()))),
+            hash_of(&(Ci::new("a".into()), Ci::new("bc".into())))
+        );
+    }
+
+    #[test]
+    fn unicode_hash() {
+        let h1 = hash_of(&Uci::unicode("Straße".into()));
+        let h2 = hash_of(CaseInsensitiveStr::unicode("STRASSE"));
+        assert_eq!(h1, h2);
+    }
+
+    #[test]
+    fn ord() {
+        let mut set: BTreeSet<Ci> = ["b", "A", "C"]
+            .into_iter()
+            .map(|s| Ci::new(s.into()))
+            .collect();
+        assert!(!set.insert(Ci::new("a".into())));
+        let v: Vec<&str> = set.iter().map(|ci| &**ci.as_inner()).collect();
+        assert_eq!(v, ["A", "b", "C"]);
+        assert!(Uci::unicode("éa".into()) < Uci::unicode("ÉB".into()));
+    }
+
+    #[test]
+    fn map_lookup() {
+        let mut map = HashMap::new();
+        map.insert(Ci::new("Content-Type".into()), 1);
+        map.insert(Ci::new(String::from("Content-Length").into()), 2);
+        assert_eq!(map.get(CaseInsensitiveStr::new("content-type")), Some(&1));
+        assert_eq!(map.get(CaseInsensitiveStr::new("CONTENT-LENGTH")), Some(&2));
+        assert_eq!(map.get(CaseInsensitiveStr::new("Accept")), None);
+        assert_eq!(map.get(&Ci::new("CONTENT-TYPE".into())), Some(&1));
+    }
+
+    #[test]
+    fn other_inner_types() {
+        let a: CaseInsensitive<&str> = "Hello".into();
+        let b: CaseInsensitive<String> = "HELLO".to_string().into();
+        assert_eq!(a, b);
+        assert_eq!(a.as_str(), "Hello");
+        assert_eq!(b.into_inner(), "HELLO");
+    }
+}
@@ -0,0 +1,178 @@
+//! Comparing [`MownStr`] and [`MownStr32`] with other string types.
+
+use super::{MownStr, MownStr32};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::cmp::Ordering;
+
+/// Implement `PartialEq` and `PartialOrd` in both directions
+/// between `$mown` (`MownStr` or `MownStr32`) and a type implementing `AsRef<str>`.
+macro_rules! impl_cmp {
+    ($mown:ident <$($lt:lifetime),*> $other:ty) => {
+        impl<'a, $($lt),*> PartialEq<$other> for $mown<'a> {
+            #[inline]
+            fn eq(&self, other: &$other) -> bool {
+                let this: &str = self;
+                this == AsRef::<str>::as_ref(other)
+            }
+        }
+
+        impl<'a, $($lt),*> PartialEq<$mown<'a>> for $other {
+            #[inline]
+            fn eq(&self, other: &$mown<'a>) -> bool {
+                let other: &str = other;
+                AsRef::<str>::as_ref(self) == other
+            }
+        }
+
+        impl<'a, $($lt),*> PartialOrd<$other> for $mown<'a> {
+            #[inline]
+            fn partial_cmp(&self, other: &$other) -> Option<Ordering> {
+                let this: &str = self;
+                this.partial_cmp(AsRef::<str>::as_ref(other))
+            }
+        }
+
+        impl<'a, $($lt),*> PartialOrd<$mown<'a>> for $other {
+            #[inline]
+            fn partial_cmp(&self, other: &$mown<'a>) -> Option<Ordering> {
+                let other: &str = other;
+                AsRef::<str>::as_ref(self).partial_cmp(other)
+            }
+        }
+    };
+}
+
+macro_rules! impl_cmp_all {
+    ($mown:ident) => {
+        impl_cmp!($mown <> str);
+        impl_cmp!($mown <'b> &'b str);
+        impl_cmp!($mown <> String);
+        impl_cmp!($mown <> Box<str>);
+        impl_cmp!($mown <'b> Cow<'b, str>);
+        impl_cmp!($mown <> Rc<str>);
+        impl_cmp!($mown <> Arc<str>);
+    };
+}
+
+impl_cmp_all!(MownStr);
+impl_cmp_all!(MownStr32);
+impl_cmp!(MownStr32 <'b> MownStr<'b>);
+
+#[cfg(test)]
+#[allow(clippy::cmp_owned)]
+mod test {
+    use super::*;
+
+    /// Check that `$lower < $upper` and `$lower == $lower2`,
+    /// with `MownStr` on the left and on the right.
+    macro_rules! check {
+        ($lower:expr, $lower2:expr, $upper:expr) => {
+            let (lower, lower2, upper) = ($lower, $lower2, $upper);
+            let mlower = MownStr::from("a");
+            let mupper = MownStr::from("b".to_string());
+            assert!(mlower == lower);
+            assert!(lower == mlower);
+            assert!(mlower != upper);
+            assert!(upper != mlower);
+            assert!(mlower < upper);
+            assert!(upper > mlower);
+            assert!(mupper > lower2);
+            assert!(lower2 < mupper);
+            assert!(mupper >= upper);
+            assert!(upper <= mupper);
+        };
+    }
+
+    #[test]
+    fn str() {
+        let mlower = MownStr::from("a");
+        assert!(mlower == *"a");
+        assert!(*"a" == mlower);
+        assert!(mlower < *"b");
+        assert!(*"b" > mlower);
+    }
+
+    #[test]
+    fn ref_str() {
+        check!("a", "a", "b");
+    }
+
+    #[test]
+    fn ref_str_other_lifetime() {
+        let a = "a".to_string();
+        let b = "b".to_string();
+        check!(&a[..], &a[..], &b[..]);
+    }
+
+    #[test]
+    fn string() {
+        check!("a".to_string(), "a".to_string(), "b".to_string());
+    }
+
+    #[test]
+    fn box_str() {
+        let a: Box<str> = "a".into();
+        let b: Box<str> = "b".into();
+        check!(a.clone(), a, b);
+    }
+
+    #[test]
+    fn cow_borrowed() {
+        check!(Cow::Borrowed("a"), Cow::Borrowed("a"), Cow::Borrowed("b"));
+    }
+
+    #[test]
+    fn cow_owned() {
+        let a: Cow<str> = Cow::Owned("a".to_string());
+        let b: Cow<str> = Cow::Owned("b".to_string());
+        check!(a.clone(), a, b);
+    }
+
+    #[test]
+    fn rc_str() {
+        let a: Rc<str> = "a".into();
+        let b: Rc<str> = "b".into();
+        check!(a.clone(), a, b);
+    }
+
+    #[test]
+    fn arc_str() {
+        let a: Arc<str> = "a".into();
+        let b: Arc<str> = "b".into();
+        check!(a.clone(), a, b);
+    }
+
+    #[test]
+    fn mownstr_same_lifetime() {
+        check!(
+            MownStr::from("a"),
+            MownStr::from("a".to_string()),
+            MownStr::from("b")
+        );
+    }
+
+    #[test]
+    fn mownstr_other_lifetime() {
+        let a = "a".to_string();
+        let b = "b".to_string();
+        check!(
+            MownStr::from(&a[..]),
+            MownStr::from(&a[..]),
+            MownStr::from(&b[..])
+        );
+    }
+
+    #[test]
+    fn mownstr_static() {
+        fn statics() -> (MownStr<'static>, MownStr<'static>) {
+            ("a".into(), "b".into())
+        }
+        let (a, b) = statics();
+        let a2 = a.clone();
+        check!(a, a2, b);
+    }
+}
@@ -0,0 +1,451 @@
+//! Deep conversion of data structures containing [`MownStr`]s.
+//!
+//! [`IntoOwned`] and [`Reborrow`] generalize
+//! [`MownStr::into_owned`] and [`MownStr::borrowed`]
+//! to types containing `MownStr`s, such as `Option<MownStr>`, `Vec<MownStr>`,
+//! tuples and maps of `MownStr`s, or any combination of them.
+//!
+//! With the `derive` feature, they can be derived for structs and enums,
+//! by recursing through their fields.
+//!
+//! # Usage
+//! ```
+//! # use mownstr::{IntoOwned, MownStr, Reborrow};
+//! fn detach(words: Vec<(MownStr, usize)>) -> Vec<(MownStr<'static>, usize)> {
+//!     words.into_owned()
+//! }
+//! let txt = "hello world".to_string();
+//! let words: Vec<_> = txt.split(' ').map(|w| (MownStr::from(w), w.len())).collect();
+//! let words = detach(words);
+//! drop(txt);
+//! assert_eq!(words[1].0, "world");
+//! assert!(words.borrowed()[1].0.is_borrowed());
+//! ```
+
+use super::{MownStr, MownStr32};
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+/// Types that can be detached from their lifetime, without copying their owned parts.
+///
+/// See [`MownStr::into_owned`].
+pub trait IntoOwned {
+    /// The `'static` version of this type.
+    type Owned: 'static;
+
+    fn into_owned(self) -> Self::Owned;
+}
+
+/// Types that can be re-borrowed, i.e. converted to a version of themselves
+/// borrowing their strings from `&'b self`.
+///
+/// Note that `borrowed` does not copy the strings,
+/// but it does allocate a new container for each collection (e.g. `Vec`) it traverses.
+/// Types containing no string (such as numbers) are simply cloned.
+///
+/// See [`MownStr::borrowed`].
+pub trait Reborrow<'b>: 'b {
+    /// The version of this type borrowing from `&'b self`.
+    type Borrowed;
+
+    fn borrowed(&'b self) -> Self::Borrowed;
+}
+
+// Strings
+
+impl IntoOwned for MownStr<'_> {
+    type Owned = MownStr<'static>;
+
+    fn into_owned(self) -> MownStr<'static> {
+        MownStr::into_owned(self)
+    }
+}
+
+impl<'a: 'b, 'b> Reborrow<'b> for MownStr<'a> {
+    type Borrowed = MownStr<'b>;
+
+    fn borrowed(&'b self) -> MownStr<'b> {
+        MownStr::borrowed(self)
+    }
+}
+
+impl IntoOwned for MownStr32<'_> {
+    type Owned = MownStr32<'static>;
+
+    fn into_owned(self) -> MownStr32<'static> {
+        MownStr32::into_owned(self)
+    }
+}
+
+impl<'a: 'b, 'b> Reborrow<'b> for MownStr32<'a> {
+    type Borrowed = MownStr32<'b>;
+
+    fn borrowed(&'b self) -> MownStr32<'b> {
+        MownStr32::borrowed(self)
+    }
+}
+
+// Types without lifetime
+
+macro_rules! impl_static {
+    ($($t:ty),*) => {
+        $(
+            impl IntoOwned for $t {
+                type Owned = $t;
+
+                fn into_owned(self) -> $t {
+                    self
+                }
+            }
+
+            impl Reborrow<'_> for $t {
+                type Borrowed = $t;
+
+                #[allow(clippy::clone_on_copy)]
+                fn borrowed(&self) -> $t {
+                    self.clone()
+                }
+            }
+        )*
+    };
+}
+
+impl_static!(bool, char, (), String, Box<str>, Arc<str>, Rc<str>);
+impl_static!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+impl IntoOwned for &'static str {
+    type Owned = &'static str;
+
+    fn into_owned(self) -> &'static str {
+        self
+    }
+}
+
+impl Reborrow<'_> for &'static str {
+    type Borrowed = &'static str;
+
+    fn borrowed(&self) -> &'static str {
+        self
+    }
+}
+
+impl<T: ?Sized + 'static> IntoOwned for PhantomData<T> {
+    type Owned = PhantomData<T>;
+
+    fn into_owned(self) -> PhantomData<T> {
+        self
+    }
+}
+
+impl<T: ?Sized + 'static> Reborrow<'_> for PhantomData<T> {
+    type Borrowed = PhantomData<T>;
+
+    fn borrowed(&self) -> PhantomData<T> {
+        PhantomData
+    }
+}
+
+// Containers
+
+impl<T: IntoOwned> IntoOwned for Option<T> {
+    type Owned = Option<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(T::into_owned)
+    }
+}
+
+impl<'b, T: Reborrow<'b>> Reborrow<'b> for Option<T> {
+    type Borrowed = Option<T::Borrowed>;
+
+    fn borrowed(&'b self) -> Self::Borrowed {
+        self.as_ref().map(T::borrowed)
+    }
+}
+
+impl<T: IntoOwned, E: IntoOwned> IntoOwned for Result<T, E> {
+    type Owned = Result<T::Owned, E::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(T::into_owned).map_err(E::into_owned)
+    }
+}
+
+impl<'b, T: Reborrow<'b>, E: Reborrow<'b>> Reborrow<'b> for Result<T, E> {
+    type Borrowed = Result<T::Borrowed, E::Borrowed>;
+
+    fn borrowed(&'b self) -> Self::Borrowed {
+        self.as_ref().map(T::borrowed).map_err(E::borrowed)
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Box<T> {
+    type Owned = Box<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        Box::new((*self).into_owned())
+    }
+}
+
+impl<'b, T: Reborrow<'b>> Reborrow<'b> for Box<T> {
+    type Borrowed = Box<T::Borrowed>;
+
+    fn borrowed(&'b self) -> Self::Borrowed {
+        Box::new((**self).borrowed())
+    }
+}
+
+impl<T: IntoOwned, const N: usize> IntoOwned for [T; N] {
+    type Owned = [T::Owned; N];
+
+    fn into_owned(self) -> Self::Owned {
+        self.map(T::into_owned)
+    }
+}
+
+impl<'b, T: Reborrow<'b>, const N: usize> Reborrow<'b> for [T; N] {
+    type Borrowed = [T::Borrowed; N];
+
+    fn borrowed(&'b self) -> Self::Borrowed {
+        core::array::from_fn(|i| self[i].borrowed())
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for Vec<T> {
+    type Owned = Vec<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter().map(T::into_owned).collect()
+    }
+}
+
+impl<'b, T: Reborrow<'b>> Reborrow<'b> for Vec<T> {
+    type Borrowed = Vec<T::Borrowed>;
+
+    fn borrowed(&'b self) -> Self::Borrowed {
+        self.iter().map(T::borrowed).collect()
+    }
+}
+
+impl<T: IntoOwned> IntoOwned for BTreeSet<T>
+where
+    T::Owned: Ord,
+{
+    type Owned = BTreeSet<T::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter().map(T::into_owned).collect()
+    }
+}
+
+impl<'b, T: Reborrow<'b>> Reborrow<'b> for BTreeSet<T>
+where
+    T::Borrowed: Ord,
+{
+    type Borrowed = BTreeSet<T::Borrowed>;
+
+    fn borrowed(&'b self) -> Self::Borrowed {
+        self.iter().map(T::borrowed).collect()
+    }
+}
+
+impl<K: IntoOwned, V: IntoOwned> IntoOwned for BTreeMap<K, V>
+where
+    K::Owned: Ord,
+{
+    type Owned = BTreeMap<K::Owned, V::Owned>;
+
+    fn into_owned(self) -> Self::Owned {
+        self.into_iter()
+            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .collect()
+    }
+}
+
+impl<'b, K: Reborrow<'b>, V: Reborrow<'b>> Reborrow<'b> for BTreeMap<K, V>
+where
+    K::Borrowed: Ord,
+{
+    type Borrowed = BTreeMap<K::Borrowed, V::Borrowed>;
+
+    fn borrowed(&'b self) -> Self::Borrowed {
+        self.iter()
+            .map(|(k, v)| (k.borrowed(), v.borrowed()))
+            .collect()
+    }
+}
+
+#[cfg(feature = "std")]
+mod hash_impls {
+    use super::{IntoOwned, Reborrow};
+    use std::collections::{HashMap, HashSet};
+    use std::hash::{BuildHasher, Hash};
+
+    impl<T: IntoOwned, S> IntoOwned for HashSet<T, S>
+    where
+        T::Owned: Hash + Eq,
+        S: BuildHasher + Clone + 'static,
+    {
+        type Owned = HashSet<T::Owned, S>;
+
+        fn into_owned(self) -> Self::Owned {
+            let mut set = HashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+            set.extend(self.into_iter().map(T::into_owned));
+            set
+        }
+    }
+
+    impl<'b, T: Reborrow<'b>, S> Reborrow<'b> for HashSet<T, S>
+    where
+        T::Borrowed: Hash + Eq,
+        S: BuildHasher + Clone + 'b,
+    {
+        type Borrowed = HashSet<T::Borrowed, S>;
+
+        fn borrowed(&'b self) -> Self::Borrowed {
+            let mut set = HashSet::with_capacity_and_hasher(self.len(), self.hasher().clone());
+            set.extend(self.iter().map(T::borrowed));
+            set
+        }
+    }
+
+    impl<K: IntoOwned, V: IntoOwned, S> IntoOwned for HashMap<K, V, S>
+    where
+        K::Owned: Hash + Eq,
+        S: BuildHasher + Clone + 'static,
+    {
+        type Owned = HashMap<K::Owned, V::Owned, S>;
+
+        fn into_owned(self) -> Self::Owned {
+            let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+            map.extend(
+                self.into_iter()
+                    .map(|(k, v)| (k.into_owned(), v.into_owned())),
+            );
+            map
+        }
+    }
+
+    impl<'b, K: Reborrow<'b>, V: Reborrow<'b>, S> Reborrow<'b> for HashMap<K, V, S>
+    where
+        K::Borrowed: Hash + Eq,
+        S: BuildHasher + Clone + 'b,
+    {
+        type Borrowed = HashMap<K::Borrowed, V::Borrowed, S>;
+
+        fn borrowed(&'b self) -> Self::Borrowed {
+            let mut map = HashMap::with_capacity_and_hasher(self.len(), self.hasher().clone());
+            map.extend(self.iter().map(|(k, v)| (k.borrowed(), v.borrowed())));
+            map
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($t:ident $i:tt),+) => {
+        impl<$($t: IntoOwned),+> IntoOwned for ($($t,)+) {
+            type Owned = ($($t::Owned,)+);
+
+            fn into_owned(self) -> Self::Owned {
+                ($(self.$i.into_owned(),)+)
+            }
+        }
+
+        impl<'b, $($t: Reborrow<'b>),+> Reborrow<'b> for ($($t,)+) {
+            type Borrowed = ($($t::Borrowed,)+);
+
+            fn borrowed(&'b self) -> Self::Borrowed {
+                ($(self.$i.borrowed(),)+)
+            }
+        }
+    };
+}
+
+impl_tuple!(A 0);
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+impl_tuple!(A 0, B 1, C 2, D 3);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+impl_tuple!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::vec;
+
+    #[test]
+    fn mownstr() {
+        let txt = "hello".to_string();
+        let mown = MownStr::from(&txt[..]);
+        assert!(mown.borrowed().is_borrowed());
+        let owned: MownStr<'static> = IntoOwned::into_owned(mown);
+        drop(txt);
+        assert!(owned.is_owned());
+        assert_eq!(owned, "hello");
+        let mown32 = MownStr32::from("hello");
+        assert!(IntoOwned::into_owned(mown32.borrowed()).is_owned());
+    }
+
+    #[test]
+    fn containers() {
+        let txt = "hello world and everyone".to_string();
+        let words: Vec<MownStr> = txt.split(' ').map(MownStr::from).collect();
+        let data = (
+            Some(words[0].clone()),
+            words.clone(),
+            Box::new(words[1].clone()),
+            42,
+        );
+        let owned = data.into_owned();
+        drop(words);
+        drop(txt);
+        assert!(owned.0.as_ref().unwrap().is_owned());
+        assert!(owned.1.iter().all(MownStr::is_owned));
+        assert!(owned.2.is_owned());
+        assert_eq!(owned.3, 42);
+
+        let borrowed = owned.borrowed();
+        assert!(borrowed.0.as_ref().unwrap().is_borrowed());
+        assert_eq!(borrowed.1[2].as_ptr(), owned.1[2].as_ptr());
+        assert!(borrowed.2.is_borrowed());
+    }
+
+    #[test]
+    fn maps() {
+        let txt = "hello".to_string();
+        let mut map = BTreeMap::new();
+        map.insert(MownStr::from(&txt[..]), vec![MownStr::from(&txt[1..])]);
+        let owned = map.into_owned();
+        drop(txt);
+        let (k, v) = owned.iter().next().unwrap();
+        assert!(k.is_owned());
+        assert_eq!(v, &["ello"]);
+        let borrowed = owned.borrowed();
+        assert!(borrowed.keys().all(MownStr::is_borrowed));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn hash_maps() {
+        use std::collections::{HashMap, HashSet};
+        let txt = "hello".to_string();
+        let mut map = HashMap::new();
+        map.insert(MownStr::from(&txt[..]), 1);
+        let set: HashSet<MownStr> = map.keys().cloned().collect();
+        let (map, set) = (map.into_owned(), set.into_owned());
+        drop(txt);
+        assert!(map.keys().all(MownStr::is_owned));
+        assert!(set.contains("hello"));
+        let map = map.borrowed();
+        assert_eq!(map.get("hello"), Some(&1));
+        assert!(map.keys().all(MownStr::is_borrowed));
+    }
+}
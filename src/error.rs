@@ -0,0 +1,43 @@
+//! Errors raised by the fallible constructors of [`MownStr`](crate::MownStr).
+
+use alloc::collections::TryReserveError;
+use core::fmt;
+
+/// The error returned by the fallible constructors of [`MownStr`](crate::MownStr),
+/// such as [`try_from_ref`](crate::MownStr::try_from_ref)
+/// or [`try_clone`](crate::MownStr::try_clone).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MownStrError {
+    /// The string is too long to be represented by a `MownStr`
+    /// (the length of the string is provided).
+    TooLong(usize),
+    /// The memory required by the `MownStr` could not be allocated.
+    AllocFailed(TryReserveError),
+}
+
+impl fmt::Display for MownStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MownStrError::TooLong(len) => {
+                write!(f, "string of length {len} is too long for MownStr")
+            }
+            MownStrError::AllocFailed(err) => fmt::Display::fmt(err, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MownStrError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MownStrError::TooLong(_) => None,
+            MownStrError::AllocFailed(err) => Some(err),
+        }
+    }
+}
+
+impl From<TryReserveError> for MownStrError {
+    fn from(other: TryReserveError) -> Self {
+        MownStrError::AllocFailed(other)
+    }
+}
@@ -0,0 +1,433 @@
+//! [`MownStr`] with a cached hash, for hash-heavy workloads.
+//!
+//! * [`HashedMownStr`] computes the hash of its content once, at construction;
+//! * [`PrehashedState`] is the [`BuildHasher`] that should be used in hash maps and sets
+//!   whose keys are `HashedMownStr`s: it reuses their cached hash
+//!   (see [`HashedMap`] and [`HashedSet`]);
+//! * [`HashedStr`] is the borrowed counterpart of `HashedMownStr`,
+//!   allowing to query such maps and sets with a `&str`.
+//!
+//! # Usage
+//! ```
+//! # use mownstr::hashed::{HashedMownStr, HashedMap, HashedStr};
+//! let mut map = HashedMap::default();
+//! map.insert(HashedMownStr::from("http://example.org/"), 42);
+//! assert_eq!(map.get(HashedStr::new("http://example.org/")), Some(&42));
+//! assert_eq!(map.get(&HashedMownStr::from("http://example.org/")), Some(&42));
+//! ```
+
+use super::MownStr;
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::hash::{BuildHasher, Hash, Hasher};
+use std::ops::Deref;
+
+/// A [`MownStr`] with a cached hash.
+///
+/// Its [`Hash`] implementation only writes the cached hash,
+/// and comparing two `HashedMownStr`s with different hashes does not compare their content.
+///
+/// NB: a `HashedMownStr` can not be [borrowed](Borrow) as a `str`,
+/// as they do not produce the same hash;
+/// it can be borrowed as a [`HashedStr`] instead.
+///
+/// Also note that the cached hash does not depend on a random seed,
+/// so maps and sets using it are not protected against HashDoS attacks.
+#[derive(Clone)]
+pub struct HashedMownStr<'a> {
+    hash: u64,
+    mown: MownStr<'a>,
+}
+
+impl<'a> HashedMownStr<'a> {
+    #[must_use]
+    pub fn new(mown: impl Into<MownStr<'a>>) -> Self {
+        let mown = mown.into();
+        let hash = hash_of(&*mown);
+        HashedMownStr { hash, mown }
+    }
+
+    /// The cached hash of this `HashedMownStr`.
+    #[must_use]
+    pub fn hash_value(&self) -> u64 {
+        self.hash
+    }
+
+    #[must_use]
+    pub fn as_mown(&self) -> &MownStr<'a> {
+        &self.mown
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> MownStr<'a> {
+        self.mown
+    }
+
+    #[must_use]
+    pub fn borrowed(&self) -> HashedMownStr<'_> {
+        HashedMownStr {
+            hash: self.hash,
+            mown: self.mown.borrowed(),
+        }
+    }
+
+    /// Detach this `HashedMownStr` from its lifetime, without recomputing its hash.
+    ///
+    /// See [`MownStr::into_owned`].
+    #[must_use]
+    pub fn into_owned(self) -> HashedMownStr<'static> {
+        HashedMownStr {
+            hash: self.hash,
+            mown: self.mown.into_owned(),
+        }
+    }
+}
+
+impl<'a> From<MownStr<'a>> for HashedMownStr<'a> {
+    fn from(other: MownStr<'a>) -> Self {
+        Self::new(other)
+    }
+}
+
+impl<'a> From<&'a str> for HashedMownStr<'a> {
+    fn from(other: &'a str) -> Self {
+        Self::new(other)
+    }
+}
+
+impl From<Box<str>> for HashedMownStr<'_> {
+    fn from(other: Box<str>) -> Self {
+        Self::new(other)
+    }
+}
+
+impl From<String> for HashedMownStr<'_> {
+    fn from(other: String) -> Self {
+        Self::new(other)
+    }
+}
+
+impl<'a> From<HashedMownStr<'a>> for MownStr<'a> {
+    fn from(other: HashedMownStr<'a>) -> Self {
+        other.mown
+    }
+}
+
+// Using a HashedMownStr as a str
+
+impl Deref for HashedMownStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.mown
+    }
+}
+
+impl AsRef<str> for HashedMownStr<'_> {
+    fn as_ref(&self) -> &str {
+        &self.mown
+    }
+}
+
+impl Borrow<HashedStr> for HashedMownStr<'_> {
+    fn borrow(&self) -> &HashedStr {
+        HashedStr::new(&self.mown)
+    }
+}
+
+/// A `str` that hashes like a [`HashedMownStr`].
+///
+/// This is the borrowed counterpart of `HashedMownStr`,
+/// allowing to look up a `&str` in a map or set with `HashedMownStr` keys.
+/// Contrarily to `HashedMownStr`, it does not cache its hash,
+/// which is computed each time it is hashed.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[repr(transparent)]
+pub struct HashedStr(str);
+
+impl HashedStr {
+    #[must_use]
+    pub fn new(txt: &str) -> &Self {
+        // SAFETY: HashedStr is a transparent wrapper around str
+        unsafe { &*(txt as *const str as *const Self) }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Hash for HashedStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // consistent with the implementation for HashedMownStr
+        state.write_u64(hash_of(&self.0));
+    }
+}
+
+impl fmt::Debug for HashedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+// Comparing HashedMownStr
+
+impl Hash for HashedMownStr<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash);
+    }
+}
+
+impl<'b> PartialEq<HashedMownStr<'b>> for HashedMownStr<'_> {
+    fn eq(&self, other: &HashedMownStr<'b>) -> bool {
+        self.hash == other.hash && *self.mown == *other.mown
+    }
+}
+
+impl Eq for HashedMownStr<'_> {}
+
+impl PartialEq<str> for HashedMownStr<'_> {
+    fn eq(&self, other: &str) -> bool {
+        *self.mown == *other
+    }
+}
+
+impl<'b> PartialEq<&'b str> for HashedMownStr<'_> {
+    fn eq(&self, other: &&'b str) -> bool {
+        *self.mown == **other
+    }
+}
+
+impl<'b> PartialEq<MownStr<'b>> for HashedMownStr<'_> {
+    fn eq(&self, other: &MownStr<'b>) -> bool {
+        *self.mown == **other
+    }
+}
+
+impl<'b> PartialOrd<HashedMownStr<'b>> for HashedMownStr<'_> {
+    fn partial_cmp(&self, other: &HashedMownStr<'b>) -> Option<std::cmp::Ordering> {
+        Some(self.mown.deref().cmp(&*other.mown))
+    }
+}
+
+impl Ord for HashedMownStr<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.mown.cmp(&other.mown)
+    }
+}
+
+// Formatting
+
+impl fmt::Debug for HashedMownStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.mown, f)
+    }
+}
+
+impl fmt::Display for HashedMownStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.mown, f)
+    }
+}
+
+// Hashing
+
+/// The [`BuildHasher`] to use with [`HashedMownStr`] keys.
+///
+/// See the [module documentation](self) for more details.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PrehashedState;
+
+impl BuildHasher for PrehashedState {
+    type Hasher = PrehashedHasher;
+
+    fn build_hasher(&self) -> PrehashedHasher {
+        PrehashedHasher::default()
+    }
+}
+
+/// The [`Hasher`] built by [`PrehashedState`].
+///
+/// If the first value it is given is a `u64` (e.g. the cached hash of a [`HashedMownStr`]),
+/// it uses it as is;
+/// otherwise, it hashes all the values it is given with a [`DefaultHasher`].
+#[derive(Clone, Debug, Default)]
+pub struct PrehashedHasher(State);
+
+#[derive(Clone, Debug, Default)]
+enum State {
+    #[default]
+    Empty,
+    Prehashed(u64),
+    Hashing(DefaultHasher),
+}
+
+impl PrehashedHasher {
+    /// The inner hasher, to hash anything but a single `u64`.
+    fn hashing(&mut self) -> &mut DefaultHasher {
+        match self.0 {
+            State::Hashing(ref mut h) => return h,
+            State::Empty => self.0 = State::Hashing(DefaultHasher::new()),
+            State::Prehashed(hash) => {
+                let mut h = DefaultHasher::new();
+                h.write_u64(hash);
+                self.0 = State::Hashing(h);
+            }
+        }
+        match self.0 {
+            State::Hashing(ref mut h) => h,
+            _ => unreachable!(),
+        }
+    }
+}
+
+impl Hasher for PrehashedHasher {
+    fn finish(&self) -> u64 {
+        match self.0 {
+            State::Empty => DefaultHasher::new().finish(),
+            State::Prehashed(hash) => hash,
+            State::Hashing(ref h) => h.finish(),
+        }
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.hashing().write(bytes);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        if let State::Empty = self.0 {
+            self.0 = State::Prehashed(i);
+        } else {
+            self.hashing().write_u64(i);
+        }
+    }
+}
+
+/// Hash `value` with a [`PrehashedHasher`].
+// NB: BuildHasher::hash_one requires Rust 1.71
+#[allow(clippy::manual_hash_one)]
+fn hash_of<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = PrehashedState.build_hasher();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// A `HashMap` with [`HashedMownStr`] keys.
+pub type HashedMap<'a, V> = HashMap<HashedMownStr<'a>, V, PrehashedState>;
+
+/// A `HashSet` of [`HashedMownStr`].
+pub type HashedSet<'a> = HashSet<HashedMownStr<'a>, PrehashedState>;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn new() {
+        let txt = "hello".to_string();
+        let hashed = HashedMownStr::from(&txt[..]);
+        assert!(hashed.as_mown().is_borrowed());
+        assert_eq!(hashed.as_ptr(), txt.as_ptr());
+        assert_eq!(hashed, "hello");
+        let hashed2 = HashedMownStr::from(txt.clone());
+        assert!(hashed2.as_mown().is_owned());
+        assert_eq!(hashed.hash_value(), hashed2.hash_value());
+        assert_eq!(hashed, hashed2);
+        assert_ne!(
+            hashed.hash_value(),
+            HashedMownStr::from("world").hash_value()
+        );
+    }
+
+    #[test]
+    fn hash_is_cached() {
+        let hashed = HashedMownStr::from("hello");
+        assert_eq!(hash_of(&hashed), hashed.hash_value());
+        let owned = hashed.clone().into_owned();
+        assert_eq!(owned.hash_value(), hashed.hash_value());
+        assert_eq!(hashed.borrowed().hash_value(), hashed.hash_value());
+    }
+
+    #[test]
+    fn same_hash_as_str() {
+        for txt in ["", "hello", "hello world and everyone"] {
+            let hashed = HashedMownStr::from(txt);
+            assert_eq!(hash_of(&hashed), hash_of(txt));
+        }
+    }
+
+    #[test]
+    fn eq_hash_mismatch() {
+        // forge a HashedMownStr with a wrong hash
+        let forged = HashedMownStr {
+            hash: 0,
+            mown: "hello".into(),
+        };
+        assert_ne!(forged, HashedMownStr::from("hello"));
+        assert_eq!(forged, "hello");
+    }
+
+    #[test]
+    fn other_values() {
+        let h = |v: &dyn Fn(&mut PrehashedHasher)| {
+            let mut hasher = PrehashedState.build_hasher();
+            v(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(h(&|s| s.write_u64(42)), 42);
+        assert_ne!(h(&|s| (42u64, 1u8).hash(s)), 42);
+        assert_ne!(h(&|s| (42u64, 1u8).hash(s)), h(&|s| (42u64, 2u8).hash(s)));
+        assert_ne!(h(&|s| (1u8, 42u64).hash(s)), h(&|s| (2u8, 42u64).hash(s)));
+    }
+
+    #[test]
+    fn map() {
+        let mut map = HashedMap::default();
+        map.insert(HashedMownStr::from("hello".to_string()), 1);
+        map.insert(HashedMownStr::from("world"), 2);
+        assert_eq!(map.get(HashedStr::new("hello")), Some(&1));
+        let world = "world".to_string();
+        assert_eq!(map.get(HashedStr::new(&world)), Some(&2));
+        assert_eq!(map.get(&HashedMownStr::from("hello")), Some(&1));
+        assert_eq!(map.get(HashedStr::new("everyone")), None);
+    }
+
+    #[test]
+    fn map_random_state() {
+        let mut map = HashMap::new();
+        map.insert(HashedMownStr::from("hello".to_string()), 1);
+        assert_eq!(map.get(HashedStr::new("hello")), Some(&1));
+        assert_eq!(map.get(&HashedMownStr::from("hello")), Some(&1));
+        assert_eq!(map.get(HashedStr::new("world")), None);
+    }
+
+    #[test]
+    fn set() {
+        let set: HashedSet = ["a", "b", "c"]
+            .into_iter()
+            .map(HashedMownStr::from)
+            .collect();
+        assert!(set.contains(HashedStr::new("a")));
+        assert!(!set.contains(HashedStr::new("d")));
+    }
+
+    #[test]
+    fn cmp() {
+        let a = HashedMownStr::from("a");
+        let b = HashedMownStr::from("b");
+        assert!(a < b);
+        assert_eq!(a.cmp(&a.clone()), std::cmp::Ordering::Equal);
+        assert_eq!(a, MownStr::from("a"));
+    }
+
+    #[test]
+    fn display() {
+        let hashed = HashedMownStr::from("hello");
+        assert_eq!(format!("{hashed:?}"), "\"hello\"");
+        assert_eq!(format!("{hashed}"), "hello");
+        assert_eq!(MownStr::from(hashed), "hello");
+    }
+}
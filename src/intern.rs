@@ -0,0 +1,321 @@
+//! String interning, producing deduplicated [`MownStr`] values.
+//!
+//! An interner stores a single copy of each distinct string it is given,
+//! and returns [borrowed](MownStr::is_borrowed) `MownStr`s pointing to that copy.
+//!
+//! * [`Interner`] is a single-threaded interner;
+//! * [`SyncInterner`] is a thread-safe interner;
+//!   the [`global`] one returns `MownStr<'static>`.
+//!
+//! # Usage
+//! ```
+//! # use mownstr::intern::Interner;
+//! let interner = Interner::new();
+//! let ms1 = interner.intern("http://example.org/");
+//! let ms2 = interner.intern("http://example.org/".to_string());
+//! assert!(ms1.is_borrowed());
+//! assert_eq!(ms1.as_ptr(), ms2.as_ptr());
+//! assert_eq!(interner.stats().hits, 1);
+//! ```
+
+use super::MownStr;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::sync::{Mutex, PoisonError};
+
+/// Statistics about the use of an interner.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct InternerStats {
+    /// Number of interned strings that were already stored in the interner.
+    pub hits: usize,
+    /// Number of interned strings that were not already stored in the interner.
+    pub misses: usize,
+    /// Total length, in bytes, of the strings stored in the interner.
+    pub bytes_stored: usize,
+    /// Total length, in bytes, of the strings that were not stored again,
+    /// because they were already in the interner.
+    pub bytes_saved: usize,
+}
+
+/// The storage shared by all interners.
+#[derive(Default)]
+struct Pool {
+    strings: HashSet<Box<str>>,
+    stats: InternerStats,
+}
+
+impl Pool {
+    /// Return a pointer to the stored copy of `other`, storing it first if necessary.
+    ///
+    /// The returned pointer remains valid as long as the pool is not cleared or dropped,
+    /// because the content of the boxes does not move when the set is resized.
+    fn get_or_insert(&mut self, other: MownStr) -> *const str {
+        if let Some(stored) = self.strings.get(&*other) {
+            self.stats.hits += 1;
+            self.stats.bytes_saved += stored.len();
+            return &**stored;
+        }
+        self.stats.misses += 1;
+        self.stats.bytes_stored += other.len();
+        let stored = other.to::<Box<str>>();
+        let ptr = &*stored as *const str;
+        self.strings.insert(stored);
+        ptr
+    }
+}
+
+/// A single-threaded string interner.
+///
+/// See the [module documentation](self) for more details.
+#[derive(Default)]
+pub struct Interner {
+    pool: RefCell<Pool>,
+}
+
+impl Interner {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return a `MownStr` borrowed from the copy of `other` stored in this interner,
+    /// storing it first if necessary.
+    ///
+    /// If `other` is an owned `String` or `MownStr`,
+    /// it is stored without copying (whenever possible).
+    pub fn intern<'s, T: Into<MownStr<'s>>>(&self, other: T) -> MownStr<'_> {
+        // NB: convert before borrowing the pool, as `into` may use this interner
+        let other = other.into();
+        let ptr = self.pool.borrow_mut().get_or_insert(other);
+        // SAFETY: the stored strings live as long as self (see Pool::get_or_insert)
+        MownStr::from_ref(unsafe { &*ptr })
+    }
+
+    /// The number of distinct strings stored in this interner.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pool.borrow().strings.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> InternerStats {
+        self.pool.borrow().stats
+    }
+
+    /// Remove all the strings stored in this interner, and reset its statistics.
+    pub fn clear(&mut self) {
+        *self.pool.get_mut() = Pool::default();
+    }
+}
+
+/// A thread-safe string interner.
+///
+/// See the [module documentation](self) for more details.
+pub struct SyncInterner {
+    // NB: HashSet::new is not const, hence the Option
+    pool: Mutex<Option<Pool>>,
+}
+
+impl SyncInterner {
+    #[must_use]
+    pub const fn new() -> Self {
+        SyncInterner {
+            pool: Mutex::new(None),
+        }
+    }
+
+    /// Return a `MownStr` borrowed from the copy of `other` stored in this interner,
+    /// storing it first if necessary.
+    ///
+    /// If `other` is an owned `String` or `MownStr`,
+    /// it is stored without copying (whenever possible).
+    pub fn intern<'s, T: Into<MownStr<'s>>>(&self, other: T) -> MownStr<'_> {
+        // NB: convert before locking the pool, as `into` may use this interner
+        let other = other.into();
+        let ptr = self.with_pool(|pool| pool.get_or_insert(other));
+        // SAFETY: the stored strings live as long as self (see Pool::get_or_insert)
+        MownStr::from_ref(unsafe { &*ptr })
+    }
+
+    /// The number of distinct strings stored in this interner.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.with_pool(|pool| pool.strings.len())
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[must_use]
+    pub fn stats(&self) -> InternerStats {
+        self.with_pool(|pool| pool.stats)
+    }
+
+    /// Remove all the strings stored in this interner, and reset its statistics.
+    pub fn clear(&mut self) {
+        *self.pool.get_mut().unwrap_or_else(PoisonError::into_inner) = None;
+    }
+
+    fn with_pool<T>(&self, f: impl FnOnce(&mut Pool) -> T) -> T {
+        // NB: the pool is never left in an inconsistent state,
+        // so it is safe to ignore poisoning
+        let mut guard = self.pool.lock().unwrap_or_else(PoisonError::into_inner);
+        f(guard.get_or_insert_with(Pool::default))
+    }
+}
+
+impl Default for SyncInterner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static GLOBAL: SyncInterner = SyncInterner::new();
+
+/// The global interner, producing `MownStr<'static>`.
+///
+/// NB: strings stored in the global interner are never freed.
+///
+/// # Usage
+/// ```
+/// # use mownstr::{intern, MownStr};
+/// let ms: MownStr<'static> = intern::global().intern("hello".to_string());
+/// assert!(ms.is_borrowed());
+/// ```
+#[must_use]
+pub fn global() -> &'static SyncInterner {
+    &GLOBAL
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn intern_str() {
+        let interner = Interner::new();
+        let txt1 = "hello".to_string();
+        let txt2 = "hello".to_string();
+        let mown1 = interner.intern(&txt1[..]);
+        let mown2 = interner.intern(&txt2[..]);
+        assert!(mown1.is_borrowed());
+        assert!(mown2.is_borrowed());
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+        assert_ne!(mown1.as_ptr(), txt1.as_ptr());
+        assert_eq!(mown1, "hello");
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn intern_owned() {
+        let interner = Interner::new();
+        let txt = "hello world and everyone".to_string();
+        let ptr = txt.as_ptr();
+        let mown1 = interner.intern(txt);
+        assert_eq!(mown1.as_ptr(), ptr);
+        let mown2 = interner.intern(MownStr::from("hello world and everyone".to_string()));
+        assert_eq!(mown2.as_ptr(), ptr);
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn intern_many() {
+        let interner = Interner::new();
+        let v: Vec<_> = (0..1000).map(|i| interner.intern(i.to_string())).collect();
+        let w: Vec<_> = (0..1000).map(|i| interner.intern(i.to_string())).collect();
+        for (i, (m1, m2)) in v.iter().zip(w.iter()).enumerate() {
+            assert_eq!(m1.as_ptr(), m2.as_ptr());
+            assert_eq!(&m1[..], i.to_string());
+        }
+        assert_eq!(interner.len(), 1000);
+    }
+
+    #[test]
+    fn stats() {
+        let mut interner = Interner::new();
+        assert!(interner.is_empty());
+        let _ = interner.intern("hello");
+        let _ = interner.intern("world");
+        let _ = interner.intern("hello");
+        let _ = interner.intern("hello");
+        assert_eq!(
+            interner.stats(),
+            InternerStats {
+                hits: 2,
+                misses: 2,
+                bytes_stored: 10,
+                bytes_saved: 10,
+            }
+        );
+        interner.clear();
+        assert!(interner.is_empty());
+        assert_eq!(interner.stats(), InternerStats::default());
+    }
+
+    #[test]
+    fn sync_interner() {
+        let interner = SyncInterner::new();
+        let (mown1, mown2) = std::thread::scope(|s| {
+            let h1 = s.spawn(|| interner.intern("hello"));
+            let h2 = s.spawn(|| interner.intern("hello".to_string()));
+            (h1.join().unwrap(), h2.join().unwrap())
+        });
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+        assert_eq!(interner.len(), 1);
+        assert_eq!(interner.stats().hits, 1);
+        assert_eq!(interner.stats().misses, 1);
+    }
+
+    /// A type whose conversion into `MownStr` uses the interner.
+    struct Reentrant<'i, I>(&'i I, &'static str);
+
+    impl<'i> From<Reentrant<'i, Interner>> for MownStr<'i> {
+        fn from(r: Reentrant<'i, Interner>) -> Self {
+            r.0.intern(r.1)
+        }
+    }
+
+    impl<'i> From<Reentrant<'i, SyncInterner>> for MownStr<'i> {
+        fn from(r: Reentrant<'i, SyncInterner>) -> Self {
+            r.0.intern(r.1)
+        }
+    }
+
+    #[test]
+    fn reentrant_intern() {
+        let interner = Interner::new();
+        let mown1 = interner.intern(Reentrant(&interner, "hello"));
+        let mown2 = interner.intern("hello");
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn reentrant_sync_intern() {
+        let interner = SyncInterner::new();
+        let mown1 = interner.intern(Reentrant(&interner, "hello"));
+        let mown2 = interner.intern("hello");
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+        assert_eq!(interner.len(), 1);
+    }
+
+    #[test]
+    fn global_interner() {
+        fn make() -> MownStr<'static> {
+            let txt = "intern::test::global_interner".to_string();
+            global().intern(&txt[..])
+        }
+        let mown1 = make();
+        let mown2 = make();
+        assert!(mown1.is_borrowed());
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+        assert_eq!(mown1, "intern::test::global_interner");
+    }
+}
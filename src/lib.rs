@@ -1,29 +1,114 @@
 //! [`MownStr`]
-//! is either a borrowed reference to a `str` or an own `Box<str>`.
-
-use std::borrow::Cow;
-use std::fmt;
-use std::hash;
-use std::marker::PhantomData;
-use std::ops::Deref;
-use std::ptr::NonNull;
-use std::slice;
-use std::str;
+//! is either a borrowed reference to a `str`, an own `Box<str>`
+//! or a shared `Arc<str>`.
+//!
+//! # Features
+//! * `std` (enabled by default):
+//!   without this feature, this crate is `no_std` (but still requires `alloc`),
+//!   and the `hashed` and `intern` modules are not available.
+//! * `serde`: implements `Serialize` and `Deserialize` for [`MownStr`].
+//! * `allocator-api2`: allows to allocate owned [`MownStr`]s in a custom allocator,
+//!   using the [`allocator_api2`](https://docs.rs/allocator-api2) crate.
+//! * `derive`: allows to derive [`IntoOwned`] and [`Reborrow`]
+//!   for structs and enums containing [`MownStr`]s.
+//! * `unicode`: Unicode normalization of [`MownStr`]s (see `MownStr::nfc`),
+//!   using the [`unicode-normalization`](https://docs.rs/unicode-normalization) crate.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::rc::Rc;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::any::TypeId;
+use core::fmt;
+use core::hash;
+use core::marker::PhantomData;
+use core::mem::ManuallyDrop;
+use core::ops::Deref;
+use core::ptr::{self, NonNull};
+use core::slice;
+use core::str;
+use core::sync::atomic::{self, AtomicUsize, Ordering};
+
+#[cfg(feature = "allocator-api2")]
+mod allocator;
+mod arena;
+pub use arena::{ArenaStats, MownArena};
+pub mod case;
+mod cmp;
+mod deep;
+pub use deep::{IntoOwned, Reborrow};
+#[cfg(feature = "derive")]
+pub use mownstr_derive::{IntoOwned, Reborrow};
+mod error;
+pub use error::MownStrError;
+#[cfg(feature = "std")]
+pub mod hashed;
+#[cfg(feature = "std")]
+pub mod intern;
+mod mown32;
+pub use mown32::MownStr32;
+mod mutable;
+pub use mutable::MownStrMut;
+mod newtype;
+#[doc(hidden)]
+pub use newtype::__private;
+mod owner;
+#[cfg(feature = "serde")]
+mod serde_impl;
+pub mod split;
+mod substr;
+mod transform;
+#[cfg(feature = "unicode")]
+mod unicode;
+#[cfg(feature = "unicode")]
+pub use unicode::NormalizedMownStr;
+mod view;
+pub use view::MownView;
 
 /// "Maybe own str":
-/// either a borrowed reference to a `str` or an owned `Box<str>`.
+/// either a borrowed reference to a `str`, an owned `Box<str>`,
+/// or a shared `Arc<str>`.
 ///
-/// It does not try to be mutable, nor generic,
+/// It does not try to be generic,
 /// which makes it lighter than, for example, `Cow<str>`.
+/// It is not primarily meant to be mutated either,
+/// but its content can be modified with [`to_mut`](MownStr::to_mut)
+/// or [`make_mut`](MownStr::make_mut).
+///
+/// Cloning a borrowed or a shared `MownStr` is cheap
+/// (the latter only increments a reference counter),
+/// while cloning an owned `Box<str>` copies the underlying string.
+///
+/// Short owned strings (up to 14 bytes on 64-bit architectures,
+/// 6 bytes on 32-bit architectures)
+/// are stored inline, in the `MownStr` itself, without any heap allocation.
+/// Longer strings built from a `String` keep its spare capacity (if any),
+/// so that converting a `String` to a `MownStr` and back does not reallocate it.
 ///
 /// # Panic
 /// The drawback is that `MownStr`
-/// does not support strings with a length > `usize::MAX/2`.
-/// Trying to convert such a large string to a `MownStr` would lead to a memory leak
-/// (but is extremely unlikely in practice anyway).
+/// does not support strings with a length > `usize::MAX/8`.
+/// Trying to convert such a large string to a `MownStr` panics
+/// (but is extremely unlikely in practice anyway,
+/// and even impossible on 64-bit architectures, as it exceeds their address space).
+/// Fallible constructors (e.g. [`try_from_ref`](MownStr::try_from_ref))
+/// return an error instead.
+//
+// NB: the order of the fields depends on endianness,
+// so that the least significant byte of `addr` and the most significant byte of `xlen`
+// are always the first and last bytes of the struct (in some order).
+// The bytes in between are used to store inline strings (see `INLINE` below).
+#[repr(C)]
 pub struct MownStr<'a> {
+    #[cfg(target_endian = "little")]
     addr: NonNull<u8>,
     xlen: usize,
+    #[cfg(not(target_endian = "little"))]
+    addr: NonNull<u8>,
     _phd: PhantomData<&'a str>,
 }
 
@@ -31,12 +116,97 @@ pub struct MownStr<'a> {
 // because NonNull<u8> does not.
 // However, it is safe to declare it as Sync and Send,
 // because MownStr is basically nothing more than a `&str`,
-// or a `Box<str>`, and both are `Sync` and `Send`.
+// a `Box<str>` or an `Arc<str>`, and all are `Sync` and `Send`
+// (custom allocations are required to be `Sync` and `Send` as well).
 unsafe impl Sync for MownStr<'_> {}
 unsafe impl Send for MownStr<'_> {}
 
-const LEN_MASK: usize = usize::MAX >> 1;
-const OWN_FLAG: usize = !LEN_MASK;
+// The three most significant bits of `xlen` hold a tag,
+// indicating which kind of string the `MownStr` holds;
+// the remaining bits hold the length of the string.
+const TAG_BITS: u32 = 3;
+const LEN_MASK: usize = usize::MAX >> TAG_BITS;
+const TAG_MASK: usize = !LEN_MASK;
+const TAG_SHIFT: u32 = usize::BITS - TAG_BITS;
+const BORROWED: usize = 0;
+const BOXED: usize = 1 << TAG_SHIFT;
+const SHARED: usize = 2 << TAG_SHIFT;
+
+// Inline strings are stored in the bytes of the struct,
+// except its first and last bytes (see the definition of `MownStr`).
+// The least significant byte of `addr` is set to 1, so that `addr` is never null,
+// and the most significant byte of `xlen` holds the tag and the length of the string.
+const INLINE: usize = 3 << TAG_SHIFT;
+const INLINE_CAP: usize = 2 * core::mem::size_of::<usize>() - 2;
+const INLINE_LEN_SHIFT: u32 = usize::BITS - 8;
+const INLINE_LEN_MASK: usize = LEN_MASK >> INLINE_LEN_SHIFT;
+
+// Owned strings with spare capacity are stored as the `String` they come from,
+// to avoid reallocating them (see `From<String>` below).
+// `addr` points to the data, and the capacity of the `String`
+// is stored (unaligned) in the spare capacity, right after the data.
+const STRING: usize = 5 << TAG_SHIFT;
+
+/// Panics if `len` is too long for a `MownStr`.
+///
+/// This can only happen on architectures with less than 64 bits
+/// (as it would otherwise exceed the address space),
+/// so on other architectures, this is only checked in debug mode.
+#[inline]
+const fn check_len(len: usize) {
+    if usize::BITS < 64 {
+        assert!(len <= LEN_MASK, "string too long for MownStr");
+    } else {
+        debug_assert!(len <= LEN_MASK, "string too long for MownStr");
+    }
+}
+
+// Custom strings are reference-counted strings, managed by arbitrary code
+// (e.g. allocated with a custom allocator, or backed by an arbitrary owner).
+// `addr` points to a `CustomHeader`, which points to the actual data.
+const CUSTOM: usize = 4 << TAG_SHIFT;
+
+/// The header of a custom string (see `CUSTOM` above).
+///
+/// Custom strings are allocated as a struct starting with a `CustomHeader`,
+/// followed by any data required to free it, which is the job of `drop`.
+#[repr(C)]
+struct CustomHeader {
+    count: AtomicUsize,
+    data: NonNull<u8>,
+    /// Free the custom string, given its header and its length.
+    drop: unsafe fn(NonNull<CustomHeader>, usize),
+}
+
+// Shared slices are custom strings (see `CUSTOM` above),
+// i.e. substrings of a shared string (see `MownStr::into_shared_slice`),
+// whose owner is that shared string (a `MownStr` tagged `SHARED` or `CUSTOM`).
+// They are tagged differently from other custom strings,
+// so that slicing them again can reuse their owner instead of their header,
+// which would add a level of indirection each time.
+const SHARED_SLICE: usize = 6 << TAG_SHIFT;
+
+/// The maximum value of `CustomHeader::count` (see the implementation of Clone for Arc).
+const MAX_COUNT: usize = isize::MAX as usize;
+
+/// Abort the process (also in `no_std` environments).
+#[cold]
+fn abort() -> ! {
+    #[cfg(feature = "std")]
+    std::process::abort();
+    #[cfg(not(feature = "std"))]
+    {
+        // panicking while panicking aborts the process
+        struct Abort;
+        impl Drop for Abort {
+            fn drop(&mut self) {
+                panic!("aborting");
+            }
+        }
+        let _abort = Abort;
+        panic!("reference count overflow");
+    }
+}
 
 impl<'a> MownStr<'a> {
     #[deprecated = "use from_ref instead. This method caused confusion with FromStr::from_str."]
@@ -47,7 +217,7 @@ impl<'a> MownStr<'a> {
 
     #[must_use]
     pub const fn from_ref(other: &'a str) -> Self {
-        debug_assert!(other.len() <= LEN_MASK);
+        check_len(other.len());
         // NB: The only 'const' constructor for NonNull is new_unchecked
         // so we need an unsafe block.
 
@@ -65,28 +235,151 @@ impl<'a> MownStr<'a> {
         }
     }
 
+    /// Build a borrowed `MownStr`, or fail if `other` is too long.
+    pub const fn try_from_ref(other: &'a str) -> Result<Self, MownStrError> {
+        if other.len() > LEN_MASK {
+            return Err(MownStrError::TooLong(other.len()));
+        }
+        Ok(Self::from_ref(other))
+    }
+
     #[must_use]
     pub const fn is_borrowed(&self) -> bool {
-        (self.xlen & OWN_FLAG) == 0
+        self.tag() == BORROWED
     }
 
+    /// Whether this `MownStr` owns its data,
+    /// either exclusively (`Box<str>` or inline string)
+    /// or [shared](Self::is_shared) (`Arc<str>`).
     #[must_use]
     pub const fn is_owned(&self) -> bool {
-        (self.xlen & OWN_FLAG) == OWN_FLAG
+        self.tag() != BORROWED
     }
 
+    /// Whether this `MownStr` shares the ownership of its data
+    /// with other `MownStr`s or `Arc<str>`s,
+    /// (this includes `MownStr`s allocated in a custom allocator).
     #[must_use]
-    pub const fn borrowed(&self) -> MownStr {
+    pub const fn is_shared(&self) -> bool {
+        matches!(self.tag(), SHARED | CUSTOM | SHARED_SLICE)
+    }
+
+    #[must_use]
+    pub const fn borrowed(&self) -> MownStr<'_> {
         MownStr {
-            addr: self.addr,
-            xlen: self.xlen & LEN_MASK,
+            addr: self.data_ptr(),
+            xlen: self.real_len(),
+            _phd: PhantomData,
+        }
+    }
+
+    /// Detach this `MownStr` from its lifetime.
+    ///
+    /// If this `MownStr` is owned, its data is moved without any copy;
+    /// if it is borrowed, the underlying string is copied.
+    #[must_use]
+    pub fn into_owned(self) -> MownStr<'static> {
+        if self.is_borrowed() {
+            MownStr::owned(&self)
+        } else {
+            let this = ManuallyDrop::new(self);
+            MownStr {
+                addr: this.addr,
+                xlen: this.xlen,
+                _phd: PhantomData,
+            }
+        }
+    }
+
+    /// Build a `MownStr<'static>` with the same content as this one.
+    ///
+    /// This is equivalent to `self.clone().into_owned()`.
+    #[must_use]
+    pub fn to_owned_static(&self) -> MownStr<'static> {
+        self.clone().into_owned()
+    }
+
+    /// Build an owned `MownStr` from a copy of `other`,
+    /// storing it inline if it is short enough.
+    fn owned(other: &str) -> Self {
+        if other.len() <= INLINE_CAP {
+            Self::inline(other)
+        } else {
+            Box::<str>::from(other).into()
+        }
+    }
+
+    fn inline(other: &str) -> Self {
+        debug_assert!(other.len() <= INLINE_CAP);
+        let mut bytes = [0; 2 * core::mem::size_of::<usize>()];
+        bytes[1..=other.len()].copy_from_slice(other.as_bytes());
+        let (word0, word1) = bytes.split_at(core::mem::size_of::<usize>());
+        let word0 = usize::from_ne_bytes(word0.try_into().unwrap());
+        let word1 = usize::from_ne_bytes(word1.try_into().unwrap());
+        #[cfg(target_endian = "little")]
+        let (addr, xlen) = (word0, word1);
+        #[cfg(not(target_endian = "little"))]
+        let (addr, xlen) = (word1, word0);
+        let addr = unsafe {
+            // SAFETY: the least significant byte of addr is set to 1
+            NonNull::new_unchecked((addr | 1) as *mut u8)
+        };
+        MownStr {
+            addr,
+            xlen: xlen | INLINE | (other.len() << INLINE_LEN_SHIFT),
             _phd: PhantomData,
         }
     }
 
+    #[inline]
+    const fn tag(&self) -> usize {
+        self.xlen & TAG_MASK
+    }
+
     #[inline]
     const fn real_len(&self) -> usize {
-        self.xlen & LEN_MASK
+        if self.tag() == INLINE {
+            (self.xlen >> INLINE_LEN_SHIFT) & INLINE_LEN_MASK
+        } else {
+            self.xlen & LEN_MASK
+        }
+    }
+
+    #[inline]
+    const fn data_ptr(&self) -> NonNull<u8> {
+        match self.tag() {
+            INLINE => {
+                // inline data starts at the second byte of the struct
+                let ptr = (self as *const Self).cast::<u8>();
+                unsafe {
+                    // SAFETY: ptr is derived from a reference, so it can not be null
+                    NonNull::new_unchecked(ptr.add(1).cast_mut())
+                }
+            }
+            CUSTOM | SHARED_SLICE => unsafe { (*self.custom_header().as_ptr()).data },
+            _ => self.addr,
+        }
+    }
+
+    #[inline]
+    const fn custom_header(&self) -> NonNull<CustomHeader> {
+        debug_assert!(matches!(self.tag(), CUSTOM | SHARED_SLICE));
+        self.addr.cast()
+    }
+
+    /// Build a custom `MownStr` (see `CUSTOM`) from its header.
+    ///
+    /// # Safety
+    /// `header` must point to a valid `CustomHeader` whose count is 1,
+    /// and whose `data` points to `len` bytes of valid UTF-8;
+    /// both must remain valid until `drop` is called by the `MownStr`.
+    unsafe fn from_custom(header: NonNull<CustomHeader>, len: usize) -> Self {
+        check_len(len);
+        MownStr {
+            addr: header.cast(),
+            xlen: len | CUSTOM,
+            _phd: PhantomData,
+        }
     }
 
     #[inline]
@@ -104,7 +397,10 @@ impl<'a> MownStr<'a> {
     // (to make it usable in Drop::drop()).
     #[inline]
     unsafe fn extract_box(&mut self) -> Box<str> {
-        debug_assert!(self.is_owned(), "extract_box() called on borrowed MownStr");
+        debug_assert!(
+            self.tag() == BOXED,
+            "extract_box() called on non-boxed MownStr"
+        );
         // extract data to make box
         let ptr = self.addr.as_ptr();
         let len = self.real_len();
@@ -116,28 +412,101 @@ impl<'a> MownStr<'a> {
         let raw = str::from_utf8_unchecked_mut(slice) as *mut str;
         Box::from_raw(raw)
     }
+
+    /// Convert an *owned* `MownStr` with spare capacity to a `String`.
+    //
+    // NB: see extract_box
+    #[inline]
+    unsafe fn extract_string(&mut self) -> String {
+        debug_assert!(
+            self.tag() == STRING,
+            "extract_string() called on non-string MownStr"
+        );
+        let ptr = self.addr.as_ptr();
+        let len = self.real_len();
+        let cap = ptr.add(len).cast::<usize>().read_unaligned();
+        // turn to borrowed, to avoid double-free
+        self.xlen = 0;
+        debug_assert!(self.is_borrowed());
+        String::from_raw_parts(ptr, len, cap)
+    }
+
+    /// Convert a *shared* `MownStr` to an `Arc`.
+    //
+    // NB: see extract_box
+    #[inline]
+    unsafe fn extract_arc(&mut self) -> Arc<str> {
+        debug_assert!(
+            self.tag() == SHARED,
+            "extract_arc() called on non-shared MownStr"
+        );
+        let raw = self.raw_str();
+        // turn to borrowed, to avoid double-free
+        self.xlen = 0;
+        debug_assert!(self.is_borrowed());
+        Arc::from_raw(raw)
+    }
+
+    #[inline]
+    fn raw_str(&self) -> *const str {
+        ptr::slice_from_raw_parts(self.addr.as_ptr(), self.real_len()) as *const str
+    }
 }
 
 impl Drop for MownStr<'_> {
     fn drop(&mut self) {
-        if self.is_owned() {
-            unsafe {
-                std::mem::drop(self.extract_box());
-            }
+        match self.tag() {
+            BOXED => unsafe {
+                core::mem::drop(self.extract_box());
+            },
+            STRING => unsafe {
+                core::mem::drop(self.extract_string());
+            },
+            SHARED => unsafe {
+                core::mem::drop(self.extract_arc());
+            },
+            CUSTOM | SHARED_SLICE => unsafe {
+                let header = self.custom_header();
+                // see the implementation of Drop for Arc
+                if (*header.as_ptr()).count.fetch_sub(1, Ordering::Release) == 1 {
+                    atomic::fence(Ordering::Acquire);
+                    ((*header.as_ptr()).drop)(header, self.real_len());
+                }
+            },
+            _ => {}
         }
     }
 }
 
 impl Clone for MownStr<'_> {
     fn clone(&self) -> Self {
-        if self.is_owned() {
-            Box::<str>::from(&**self).into()
-        } else {
-            MownStr {
+        match self.tag() {
+            BOXED | STRING => Self::owned(self),
+            SHARED => {
+                // SAFETY: the arc is not dropped, so its reference count is left unchanged
+                let arc = ManuallyDrop::new(unsafe { Arc::from_raw(self.raw_str()) });
+                Arc::clone(&arc).into()
+            }
+            CUSTOM | SHARED_SLICE => {
+                let header = self.custom_header();
+                let old = unsafe { (*header.as_ptr()).count.fetch_add(1, Ordering::Relaxed) };
+                // see the implementation of Clone for Arc:
+                // clones can be forgotten, so the count must be prevented from overflowing,
+                // which would free the string while it is still in use
+                if old > MAX_COUNT {
+                    abort();
+                }
+                MownStr {
+                    addr: self.addr,
+                    xlen: self.xlen,
+                    _phd: self._phd,
+                }
+            }
+            _ => MownStr {
                 addr: self.addr,
                 xlen: self.xlen,
                 _phd: self._phd,
-            }
+            },
         }
     }
 }
@@ -153,14 +522,33 @@ impl<'a> From<&'a str> for MownStr<'a> {
 impl From<Box<str>> for MownStr<'_> {
     fn from(other: Box<str>) -> Self {
         let len = other.len();
-        debug_assert!(len <= LEN_MASK);
+        check_len(len);
         let addr = Box::leak(other).as_mut_ptr();
         let addr = unsafe {
             // SAFETY: ptr can not be null,
             NonNull::new_unchecked(addr)
         };
 
-        let xlen = len | OWN_FLAG;
+        let xlen = len | BOXED;
+        MownStr {
+            addr,
+            xlen,
+            _phd: PhantomData,
+        }
+    }
+}
+
+impl From<Arc<str>> for MownStr<'_> {
+    fn from(other: Arc<str>) -> Self {
+        let len = other.len();
+        check_len(len);
+        let addr = Arc::into_raw(other).cast::<u8>().cast_mut();
+        let addr = unsafe {
+            // SAFETY: ptr can not be null,
+            NonNull::new_unchecked(addr)
+        };
+
+        let xlen = len | SHARED;
         MownStr {
             addr,
             xlen,
@@ -170,8 +558,33 @@ impl From<Box<str>> for MownStr<'_> {
 }
 
 impl From<String> for MownStr<'_> {
+    /// Short strings are stored inline.
+    /// Otherwise, if `other` has enough spare capacity (at least `size_of::<usize>()` bytes),
+    /// its buffer is kept as is, without any reallocation;
+    /// else it is converted to a `Box<str>`.
     fn from(other: String) -> Self {
-        other.into_boxed_str().into()
+        let len = other.len();
+        if len <= INLINE_CAP {
+            Self::inline(&other)
+        } else if other.capacity() - len >= core::mem::size_of::<usize>() {
+            check_len(len);
+            let mut other = ManuallyDrop::new(other);
+            let cap = other.capacity();
+            let ptr = other.as_mut_ptr();
+            let addr = unsafe {
+                // SAFETY: the spare capacity has room for cap
+                ptr.add(len).cast::<usize>().write_unaligned(cap);
+                // SAFETY: ptr can not be null,
+                NonNull::new_unchecked(ptr)
+            };
+            MownStr {
+                addr,
+                xlen: len | STRING,
+                _phd: PhantomData,
+            }
+        } else {
+            other.into_boxed_str().into()
+        }
     }
 }
 
@@ -184,13 +597,92 @@ impl<'a> From<Cow<'a, str>> for MownStr<'a> {
     }
 }
 
+// Construct a borrowed MownStr from a reference to an owned string
+
+impl<'a> From<&'a String> for MownStr<'a> {
+    fn from(other: &'a String) -> Self {
+        Self::from_ref(other)
+    }
+}
+
+impl<'a> From<&'a Box<str>> for MownStr<'a> {
+    fn from(other: &'a Box<str>) -> Self {
+        Self::from_ref(other)
+    }
+}
+
+impl<'a> From<&'a Arc<str>> for MownStr<'a> {
+    fn from(other: &'a Arc<str>) -> Self {
+        Self::from_ref(other)
+    }
+}
+
+impl<'a> From<&'a Rc<str>> for MownStr<'a> {
+    fn from(other: &'a Rc<str>) -> Self {
+        Self::from_ref(other)
+    }
+}
+
+impl<'a> From<&'a Cow<'_, str>> for MownStr<'a> {
+    fn from(other: &'a Cow<'_, str>) -> Self {
+        Self::from_ref(other)
+    }
+}
+
+impl<'a> From<&'a MownStr<'_>> for MownStr<'a> {
+    /// Equivalent to [`borrowed`](MownStr::borrowed).
+    fn from(other: &'a MownStr<'_>) -> Self {
+        other.borrowed()
+    }
+}
+
+impl From<char> for MownStr<'_> {
+    /// The resulting `MownStr` is owned, and stored inline.
+    fn from(other: char) -> Self {
+        Self::inline(other.encode_utf8(&mut [0; 4]))
+    }
+}
+
+// Fallible construction
+
+impl MownStr<'_> {
+    /// Build an owned `MownStr` from a `Box<str>`, or fail if it is too long.
+    pub fn try_from_box(other: Box<str>) -> Result<Self, MownStrError> {
+        if other.len() > LEN_MASK {
+            return Err(MownStrError::TooLong(other.len()));
+        }
+        Ok(other.into())
+    }
+
+    /// Build an owned `MownStr` from a `String`, or fail if it is too long.
+    pub fn try_from_string(other: String) -> Result<Self, MownStrError> {
+        if other.len() > LEN_MASK {
+            return Err(MownStrError::TooLong(other.len()));
+        }
+        Ok(other.into())
+    }
+
+    /// Clone this `MownStr`, or fail if the required memory can not be allocated.
+    ///
+    /// Only `MownStr`s owning a `Box<str>` require an allocation when cloned.
+    pub fn try_clone(&self) -> Result<Self, MownStrError> {
+        if !matches!(self.tag(), BOXED | STRING) || self.len() <= INLINE_CAP {
+            return Ok(self.clone());
+        }
+        let mut copy = String::new();
+        copy.try_reserve_exact(self.len())?;
+        copy.push_str(self);
+        Ok(copy.into_boxed_str().into())
+    }
+}
+
 // Using a MownStr as a str
 
 impl Deref for MownStr<'_> {
     type Target = str;
 
     fn deref(&self) -> &str {
-        let ptr = self.addr.as_ptr();
+        let ptr = self.data_ptr().as_ptr();
         let len = self.real_len();
         unsafe {
             let slice = slice::from_raw_parts(ptr, len);
@@ -205,7 +697,7 @@ impl AsRef<str> for MownStr<'_> {
     }
 }
 
-impl std::borrow::Borrow<str> for MownStr<'_> {
+impl core::borrow::Borrow<str> for MownStr<'_> {
     fn borrow(&self) -> &str {
         self
     }
@@ -219,52 +711,26 @@ impl hash::Hash for MownStr<'_> {
     }
 }
 
-impl PartialEq for MownStr<'_> {
-    fn eq(&self, other: &Self) -> bool {
+impl<'b> PartialEq<MownStr<'b>> for MownStr<'_> {
+    fn eq(&self, other: &MownStr<'b>) -> bool {
         **self == **other
     }
 }
 
 impl Eq for MownStr<'_> {}
 
-impl PartialOrd for MownStr<'_> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+impl<'b> PartialOrd<MownStr<'b>> for MownStr<'_> {
+    fn partial_cmp(&self, other: &MownStr<'b>) -> Option<core::cmp::Ordering> {
+        Some(self.deref().cmp(&**other))
     }
 }
 
 impl Ord for MownStr<'_> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
         self.deref().cmp(&**other)
     }
 }
 
-// Comparing MownStr with str
-
-impl<'a> PartialEq<&'a str> for MownStr<'a> {
-    fn eq(&self, other: &&'a str) -> bool {
-        &**self == *other
-    }
-}
-
-impl<'a> PartialOrd<&'a str> for MownStr<'a> {
-    fn partial_cmp(&self, other: &&'a str) -> Option<std::cmp::Ordering> {
-        self.deref().partial_cmp(*other)
-    }
-}
-
-impl<'a> PartialEq<MownStr<'a>> for &'a str {
-    fn eq(&self, other: &MownStr<'a>) -> bool {
-        self == &&**other
-    }
-}
-
-impl<'a> PartialOrd<MownStr<'a>> for &'a str {
-    fn partial_cmp(&self, other: &MownStr<'a>) -> Option<std::cmp::Ordering> {
-        self.partial_cmp(&&**other)
-    }
-}
-
 // Formatting
 
 impl fmt::Debug for MownStr<'_> {
@@ -288,15 +754,31 @@ impl<'a> From<MownStr<'a>> for Box<str> {
 }
 
 impl<'a> From<MownStr<'a>> for String {
-    fn from(other: MownStr<'a>) -> Self {
-        other.to()
+    /// If `other` was built from a `String`, that `String` is returned without copying.
+    fn from(mut other: MownStr<'a>) -> Self {
+        if other.tag() == STRING {
+            unsafe { other.extract_string() }
+        } else {
+            other.to()
+        }
+    }
+}
+
+impl<'a> From<MownStr<'a>> for Arc<str> {
+    /// If `other` was built from an `Arc<str>`, that `Arc` is returned without copying.
+    fn from(mut other: MownStr<'a>) -> Self {
+        if other.tag() == SHARED {
+            unsafe { other.extract_arc() }
+        } else {
+            Arc::from(&*other)
+        }
     }
 }
 
 impl<'a> From<MownStr<'a>> for Cow<'a, str> {
     fn from(other: MownStr<'a>) -> Self {
         if other.is_owned() {
-            other.to::<String>().into()
+            String::from(other).into()
         } else {
             unsafe { other.make_ref() }.into()
         }
@@ -310,6 +792,12 @@ impl<'a> MownStr<'a> {
     /// This can not be implemented with the `From` trait,
     /// because this would conflict with `From<MownStr<'a>>`.
     ///
+    /// A `MownStr` built from an `Arc<str>` gives its `Arc` back without copying
+    /// if `T` is `Arc<str>` (otherwise, the string is copied).
+    /// Likewise, a `MownStr` built from a `String` with spare capacity
+    /// gives that `String` back (with its capacity) if `T` is `String`
+    /// (otherwise, it goes through a `Box<str>`, which shrinks it).
+    ///
     /// # Usage
     /// ```
     /// # use mownstr::MownStr;
@@ -325,20 +813,64 @@ impl<'a> MownStr<'a> {
     where
         T: From<&'a str> + From<Box<str>>,
     {
-        if self.is_owned() {
-            unsafe { self.extract_box() }.into()
-        } else {
-            unsafe { self.make_ref() }.into()
+        match self.tag() {
+            BOXED => unsafe { self.extract_box() }.into(),
+            STRING => match cast::<String, T>(unsafe { self.extract_string() }) {
+                Ok(txt) => txt,
+                Err(txt) => txt.into_boxed_str().into(),
+            },
+            SHARED => match cast::<Arc<str>, T>(unsafe { self.extract_arc() }) {
+                Ok(arc) => arc,
+                Err(arc) => Box::<str>::from(&*arc).into(),
+            },
+            INLINE | CUSTOM | SHARED_SLICE => Box::<str>::from(&*self).into(),
+            _ => unsafe { self.make_ref() }.into(),
         }
     }
 }
 
+/// Convert `value` to `T` if `T` is actually `U`, or give it back otherwise.
+///
+/// NB: `T` needs not be `'static`, but `U` must not have any lifetime parameter,
+/// as lifetimes are erased by the comparison.
+fn cast<U: 'static, T>(value: U) -> Result<T, U> {
+    // A TypeId for any type, erasing its lifetimes (see the `typeid` crate).
+    trait NonStaticAny {
+        fn get_type_id(&self) -> TypeId
+        where
+            Self: 'static;
+    }
+    impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+        fn get_type_id(&self) -> TypeId
+        where
+            Self: 'static,
+        {
+            TypeId::of::<T>()
+        }
+    }
+    let phantom = PhantomData::<T>;
+    // SAFETY: the lifetimes of T are only erased to compute its TypeId,
+    // no value of type T is involved
+    let erased = unsafe {
+        core::mem::transmute::<&dyn NonStaticAny, &(dyn NonStaticAny + 'static)>(&phantom)
+    };
+    if erased.get_type_id() == TypeId::of::<U>() {
+        let value = ManuallyDrop::new(value);
+        // SAFETY: T is U, as U has no lifetime parameter
+        Ok(unsafe { ptr::read((&*value as *const U).cast::<T>()) })
+    } else {
+        Err(value)
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::eq_op)]
 mod test {
-    use super::MownStr;
+    use super::{MownStr, MownStrError, BOXED, INLINE, INLINE_CAP, STRING};
     use std::borrow::Cow;
     use std::collections::HashSet;
+    use std::rc::Rc;
+    use std::sync::Arc;
 
     #[test]
     fn size() {
@@ -388,6 +920,164 @@ mod test {
         assert!(mown.is_owned());
     }
 
+    #[test]
+    fn build_string_from_string() {
+        let mut txt = String::with_capacity(64);
+        txt.push_str("hello world and everyone");
+        let ptr = txt.as_ptr();
+        let mown: MownStr = txt.into();
+        assert!(mown.is_owned());
+        assert!(!mown.is_shared());
+        assert_eq!(mown.tag(), STRING);
+        assert_eq!(mown.as_ptr(), ptr);
+        assert_eq!(mown, "hello world and everyone");
+
+        let txt = String::from(mown);
+        assert_eq!(txt.as_ptr(), ptr);
+        assert_eq!(txt.capacity(), 64);
+        assert_eq!(txt, "hello world and everyone");
+    }
+
+    #[test]
+    fn string_to_string() {
+        let mut txt = String::with_capacity(64);
+        txt.push_str("hello world and everyone");
+        let ptr = txt.as_ptr();
+        let mown: MownStr = txt.into();
+        assert_eq!(mown.tag(), STRING);
+        let txt = mown.to::<String>();
+        assert_eq!(txt, "hello world and everyone");
+        assert_eq!(txt.as_ptr(), ptr);
+        assert_eq!(txt.capacity(), 64);
+
+        let mown: MownStr = txt.into();
+        let bx = mown.to::<Box<str>>();
+        assert_eq!(&*bx, "hello world and everyone");
+    }
+
+    #[test]
+    fn build_boxed_from_string() {
+        let txt = "hello world and everyone".to_string();
+        let mown: MownStr = txt.into();
+        assert_eq!(mown.tag(), BOXED);
+
+        let mut txt = String::with_capacity(25);
+        txt.push_str("hello world and everyone");
+        let mown: MownStr = txt.into();
+        assert_eq!(mown.tag(), BOXED);
+        assert_eq!(mown, "hello world and everyone");
+    }
+
+    #[test]
+    fn string_to() {
+        let mut txt = String::with_capacity(64);
+        txt.push_str("hello world and everyone");
+        let mown: MownStr = txt.into();
+        assert_eq!(mown.clone().tag(), BOXED);
+        assert_eq!(mown.try_clone().unwrap(), mown);
+        assert_eq!(&*mown.clone().to::<Box<str>>(), "hello world and everyone");
+        assert_eq!(&*Arc::<str>::from(mown.clone()), "hello world and everyone");
+        assert!(matches!(Cow::from(mown.clone()), Cow::Owned(_)));
+        let mown2 = mown.borrowed();
+        assert!(mown2.is_borrowed());
+        assert_eq!(mown2.as_ptr(), mown.as_ptr());
+    }
+
+    #[test]
+    fn build_inline_from_string() {
+        let mown: MownStr = "hello".to_string().into();
+        assert!(mown.is_owned());
+        assert_eq!(mown.tag(), INLINE);
+        assert_eq!(mown, "hello");
+    }
+
+    #[test]
+    fn build_inline_empty() {
+        let mown: MownStr = String::new().into();
+        assert_eq!(mown.tag(), INLINE);
+        assert_eq!(mown, "");
+    }
+
+    #[test]
+    fn build_inline_max() {
+        let txt = "é".repeat(INLINE_CAP / 2);
+        let mown: MownStr = txt.clone().into();
+        assert_eq!(mown.tag(), INLINE);
+        assert_eq!(mown, &txt[..]);
+
+        let txt = "a".repeat(INLINE_CAP + 1);
+        let mown: MownStr = txt.clone().into();
+        assert!(mown.is_owned());
+        assert_ne!(mown.tag(), INLINE);
+        assert_eq!(mown, &txt[..]);
+    }
+
+    #[test]
+    fn inline_niche() {
+        let txt = "\0".repeat(INLINE_CAP);
+        let v: Vec<Option<MownStr>> = vec![Some(txt.clone().into()), None];
+        assert!(v[0].is_some());
+        assert!(v[1].is_none());
+        assert_eq!(v[0].as_deref(), Some(&txt[..]));
+    }
+
+    #[test]
+    fn inline_moved() {
+        let v: Vec<MownStr> = (0..100).map(|i| i.to_string().into()).collect();
+        for (i, mown) in v.iter().enumerate() {
+            assert_eq!(mown.tag(), INLINE);
+            assert_eq!(&mown[..], i.to_string());
+        }
+    }
+
+    #[test]
+    fn build_shared_from_arc() {
+        let arc: Arc<str> = "hello".into();
+        let mown: MownStr = arc.clone().into();
+        assert!(mown.is_owned());
+        assert!(mown.is_shared());
+        assert_eq!(mown, "hello");
+        assert_eq!(Arc::strong_count(&arc), 2);
+        drop(mown);
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    #[test]
+    fn build_shared_empty() {
+        let mown: MownStr = Arc::<str>::from("").into();
+        assert!(mown.is_shared());
+        assert_eq!(mown, "");
+    }
+
+    #[test]
+    fn try_build() {
+        let mown = MownStr::try_from_ref("hello").unwrap();
+        assert!(mown.is_borrowed());
+        assert_eq!(mown, "hello");
+        let mown = MownStr::try_from_box("hello".into()).unwrap();
+        assert_eq!(mown.tag(), BOXED);
+        assert_eq!(mown, "hello");
+        let mown = MownStr::try_from_string("hello".into()).unwrap();
+        assert_eq!(mown.tag(), INLINE);
+        assert_eq!(mown, "hello");
+    }
+
+    #[test]
+    fn try_build_const() {
+        const MOWN: Result<MownStr<'static>, MownStrError> = MownStr::try_from_ref("hello");
+        assert_eq!(MOWN.as_deref(), Ok("hello"));
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            MownStrError::TooLong(42).to_string(),
+            "string of length 42 is too long for MownStr"
+        );
+        let err = Vec::<u8>::new().try_reserve(usize::MAX).unwrap_err();
+        assert_eq!(MownStrError::from(err.clone()).to_string(), err.to_string());
+    }
+
     #[test]
     fn build_borrowed_from_cow() {
         let mown: MownStr = Cow::Borrowed("hello").into();
@@ -400,6 +1090,43 @@ mod test {
         assert!(mown.is_owned());
     }
 
+    #[test]
+    fn build_borrowed_from_refs() {
+        let string = "hello".to_string();
+        let bx: Box<str> = "hello".into();
+        let arc: Arc<str> = "hello".into();
+        let rc: Rc<str> = "hello".into();
+        let cow1: Cow<str> = Cow::Borrowed("hello");
+        let cow2: Cow<str> = Cow::Owned("hello".to_string());
+        let mown: MownStr = "hello".to_string().into();
+        let v = [
+            (MownStr::from(&string), string.as_ptr()),
+            (MownStr::from(&bx), bx.as_ptr()),
+            (MownStr::from(&arc), arc.as_ptr()),
+            (MownStr::from(&rc), rc.as_ptr()),
+            (MownStr::from(&cow1), cow1.as_ptr()),
+            (MownStr::from(&cow2), cow2.as_ptr()),
+            (MownStr::from(&mown), mown.as_ptr()),
+        ];
+        for (m, ptr) in v {
+            assert!(m.is_borrowed());
+            assert_eq!(m.as_ptr(), ptr);
+            assert_eq!(m, "hello");
+        }
+        assert_eq!(Arc::strong_count(&arc), 1);
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn build_inline_from_char() {
+        for c in ['a', 'é', '€', '😀'] {
+            let mown = MownStr::from(c);
+            assert!(mown.is_owned());
+            assert_eq!(mown.tag(), INLINE);
+            assert_eq!(mown, &c.to_string()[..]);
+        }
+    }
+
     #[test]
     fn borrowed() {
         let mown1: MownStr = "hello".to_string().into();
@@ -408,6 +1135,161 @@ mod test {
         assert_eq!(mown1, mown2);
     }
 
+    #[test]
+    fn clone_owned() {
+        let mown1: MownStr = "hello".to_string().into_boxed_str().into();
+        let mown2 = mown1.clone();
+        assert!(mown2.is_owned());
+        assert_eq!(mown2.tag(), INLINE);
+        assert_eq!(mown1, mown2);
+
+        let mown1: MownStr = "hello world and everyone".to_string().into();
+        let mown2 = mown1.clone();
+        assert!(mown2.is_owned());
+        assert_ne!(mown2.tag(), INLINE);
+        assert_ne!(mown1.as_ptr(), mown2.as_ptr());
+        assert_eq!(mown1, mown2);
+    }
+
+    #[test]
+    fn try_clone() {
+        let mown1: MownStr = "hello world and everyone".to_string().into();
+        let mown2 = mown1.try_clone().unwrap();
+        assert_eq!(mown2.tag(), BOXED);
+        assert_ne!(mown1.as_ptr(), mown2.as_ptr());
+        assert_eq!(mown1, mown2);
+
+        let mown1: MownStr = "hello".into();
+        let mown2 = mown1.try_clone().unwrap();
+        assert!(mown2.is_borrowed());
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+
+        let arc: Arc<str> = "hello".into();
+        let mown1: MownStr = arc.clone().into();
+        let mown2 = mown1.try_clone().unwrap();
+        assert!(mown2.is_shared());
+        assert_eq!(Arc::strong_count(&arc), 3);
+    }
+
+    #[test]
+    fn clone_inline() {
+        let mown1: MownStr = "hello".to_string().into();
+        let mown2 = mown1.clone();
+        assert_eq!(mown2.tag(), INLINE);
+        assert_eq!(mown1, mown2);
+    }
+
+    #[test]
+    fn clone_shared() {
+        let arc: Arc<str> = "hello".into();
+        let mown1: MownStr = arc.clone().into();
+        let mown2 = mown1.clone();
+        assert!(mown2.is_shared());
+        assert_eq!(mown2.as_ptr(), arc.as_ptr());
+        assert_eq!(Arc::strong_count(&arc), 3);
+        drop(mown1);
+        drop(mown2);
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    #[test]
+    fn borrowed_inline() {
+        let mown1: MownStr = "hello".to_string().into();
+        let mown2 = mown1.borrowed();
+        assert!(mown2.is_borrowed());
+        assert_eq!(mown2.as_ptr(), mown1.as_ptr());
+        assert_eq!(mown1, mown2);
+    }
+
+    #[test]
+    fn inline_to() {
+        let mown: MownStr = "hello".to_string().into();
+        assert_eq!(mown.clone().to::<Box<str>>(), Box::<str>::from("hello"));
+        assert_eq!(mown.clone().to::<String>(), "hello");
+        assert_eq!(&*mown.clone().to::<Arc<str>>(), "hello");
+        assert_eq!(&*Arc::<str>::from(mown.clone()), "hello");
+        let cow = Cow::from(mown);
+        assert!(matches!(cow, Cow::Owned(_)));
+        assert_eq!(cow, "hello");
+    }
+
+    #[test]
+    fn into_owned_borrowed() {
+        let txt = "hello world and everyone".to_string();
+        let mown: MownStr<'static> = MownStr::from(&txt[..]).into_owned();
+        assert!(mown.is_owned());
+        assert_ne!(mown.as_ptr(), txt.as_ptr());
+        assert_eq!(mown, &txt[..]);
+    }
+
+    #[test]
+    fn into_owned_owned() {
+        let mown1: MownStr = "hello world and everyone".to_string().into();
+        let ptr = mown1.as_ptr();
+        let mown2: MownStr<'static> = mown1.into_owned();
+        assert!(mown2.is_owned());
+        assert_eq!(mown2.as_ptr(), ptr);
+        assert_eq!(mown2, "hello world and everyone");
+
+        let mown1: MownStr = "hello".to_string().into();
+        let mown2: MownStr<'static> = mown1.into_owned();
+        assert_eq!(mown2.tag(), INLINE);
+        assert_eq!(mown2, "hello");
+    }
+
+    #[test]
+    fn into_owned_shared() {
+        let arc: Arc<str> = "hello".into();
+        let mown: MownStr<'static> = MownStr::from(arc.clone()).into_owned();
+        assert!(mown.is_shared());
+        assert_eq!(Arc::strong_count(&arc), 2);
+        drop(mown);
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    #[test]
+    fn to_owned_static() {
+        let txt = "hello world and everyone".to_string();
+        let mown1 = MownStr::from(&txt[..]);
+        let mown2: MownStr<'static> = mown1.to_owned_static();
+        assert!(mown1.is_borrowed());
+        assert!(mown2.is_owned());
+        assert_eq!(mown1, mown2);
+        drop(mown1);
+        drop(txt);
+        assert_eq!(mown2, "hello world and everyone");
+    }
+
+    #[test]
+    fn borrowed_shared() {
+        let mown1: MownStr = Arc::<str>::from("hello").into();
+        let mown2 = mown1.borrowed();
+        assert!(mown2.is_borrowed());
+        assert_eq!(mown1, mown2);
+    }
+
+    #[test]
+    fn shared_to_arc() {
+        let arc: Arc<str> = "hello".into();
+        let mown: MownStr = arc.clone().into();
+        let arc2 = Arc::<str>::from(mown);
+        assert!(Arc::ptr_eq(&arc, &arc2));
+        assert_eq!(Arc::strong_count(&arc), 2);
+
+        let mown: MownStr = arc.clone().into();
+        let arc3 = mown.to::<Arc<str>>();
+        assert!(Arc::ptr_eq(&arc, &arc3));
+        assert_eq!(Arc::strong_count(&arc), 3);
+
+        let mown: MownStr = arc.clone().into();
+        let bx = mown.to::<Box<str>>();
+        assert_eq!(&*bx, "hello");
+        let mown: MownStr = arc.clone().into();
+        let rc = mown.to::<Rc<str>>();
+        assert_eq!(&*rc, "hello");
+        assert_eq!(Arc::strong_count(&arc), 3);
+    }
+
     #[test]
     fn deref() {
         let txt = "hello";
@@ -417,6 +1299,9 @@ mod test {
         let mown2: MownStr = txt.to_string().into();
         assert_eq!(&*mown2, txt);
         assert_eq!(&mown2[..], txt);
+        let mown3: MownStr = Arc::<str>::from(txt).into();
+        assert_eq!(&*mown3, txt);
+        assert_eq!(&mown3[..], txt);
     }
 
     #[test]
@@ -0,0 +1,578 @@
+//! A compact variant of [`MownStr`], with 32-bit lengths.
+
+use super::{MownStr, MownStrError};
+use alloc::borrow::Cow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+use core::hash;
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::ptr::NonNull;
+use core::slice;
+use core::str;
+
+/// A compact variant of [`MownStr`]:
+/// either a borrowed reference to a `str`, or an owned `Box<str>`.
+///
+/// It is 12 bytes long on 64-bit architectures
+/// (and only requires an alignment of 4 bytes),
+/// at the expense of only supporting strings with a length < 2GiB.
+/// Contrarily to `MownStr`, it does not support inline or shared strings.
+///
+/// # Panic
+/// Trying to convert a string of 2GiB or more to a `MownStr32` panics.
+/// Fallible constructors (e.g. [`try_from_ref`](MownStr32::try_from_ref))
+/// return an error instead.
+///
+/// # Usage
+/// ```
+/// # use mownstr::{MownStr, MownStr32};
+/// let ms32 = MownStr32::from("hello");
+/// assert!(ms32.is_borrowed());
+/// let ms = MownStr::from(ms32);
+/// assert!(ms.is_borrowed());
+/// let ms32 = MownStr32::try_from(ms).unwrap();
+/// assert_eq!(ms32, "hello");
+/// ```
+//
+// NB: as the struct is packed, its fields must be copied before being used
+// (references to them may be misaligned).
+#[repr(C, packed(4))]
+pub struct MownStr32<'a> {
+    addr: NonNull<u8>,
+    xlen: u32,
+    _phd: PhantomData<&'a str>,
+}
+
+// MownStr32 does not implement `Sync` and `Send` by default,
+// because NonNull<u8> does not.
+// However, it is safe to declare it as Sync and Send,
+// because MownStr32 is basically nothing more than a `&str` or a `Box<str>`.
+unsafe impl Sync for MownStr32<'_> {}
+unsafe impl Send for MownStr32<'_> {}
+
+// The most significant bit of `xlen` indicates whether the string is owned;
+// the remaining bits hold the length of the string.
+const OWNED: u32 = 1 << 31;
+const LEN_MASK: u32 = !OWNED;
+
+/// Panics if `len` is too long for a `MownStr32`.
+#[inline]
+const fn check_len(len: usize) {
+    assert!(len <= LEN_MASK as usize, "string too long for MownStr32");
+}
+
+impl<'a> MownStr32<'a> {
+    #[must_use]
+    pub const fn from_ref(other: &'a str) -> Self {
+        check_len(other.len());
+        // SAFETY: we need a *mut u8 for new_unchecked,
+        //         but MownStr32 will never mutate its content
+        let ptr = other.as_ptr().cast_mut();
+        let addr = unsafe {
+            // SAFETY: ptr can not be null,
+            NonNull::new_unchecked(ptr)
+        };
+        MownStr32 {
+            addr,
+            xlen: other.len() as u32,
+            _phd: PhantomData,
+        }
+    }
+
+    /// Build a borrowed `MownStr32`, or fail if `other` is too long.
+    pub const fn try_from_ref(other: &'a str) -> Result<Self, MownStrError> {
+        if other.len() > LEN_MASK as usize {
+            return Err(MownStrError::TooLong(other.len()));
+        }
+        Ok(Self::from_ref(other))
+    }
+
+    #[must_use]
+    pub const fn is_borrowed(&self) -> bool {
+        self.xlen & OWNED == 0
+    }
+
+    #[must_use]
+    pub const fn is_owned(&self) -> bool {
+        self.xlen & OWNED != 0
+    }
+
+    #[must_use]
+    pub const fn borrowed(&self) -> MownStr32<'_> {
+        MownStr32 {
+            addr: self.addr,
+            xlen: self.xlen & LEN_MASK,
+            _phd: PhantomData,
+        }
+    }
+
+    /// Detach this `MownStr32` from its lifetime.
+    ///
+    /// If this `MownStr32` is owned, its `Box<str>` is moved without any copy;
+    /// if it is borrowed, the underlying string is copied.
+    #[must_use]
+    pub fn into_owned(self) -> MownStr32<'static> {
+        if self.is_borrowed() {
+            Box::<str>::from(&*self).into()
+        } else {
+            let this = core::mem::ManuallyDrop::new(self);
+            MownStr32 {
+                addr: this.addr,
+                xlen: this.xlen,
+                _phd: PhantomData,
+            }
+        }
+    }
+
+    /// Build an owned `MownStr32` from a `Box<str>`, or fail if it is too long.
+    pub fn try_from_box(other: Box<str>) -> Result<Self, MownStrError> {
+        if other.len() > LEN_MASK as usize {
+            return Err(MownStrError::TooLong(other.len()));
+        }
+        Ok(other.into())
+    }
+
+    /// Build an owned `MownStr32` from a `String`, or fail if it is too long.
+    ///
+    /// NB: the `String` is converted to a `Box<str>`,
+    /// which shrinks it (and may reallocate it) if it has spare capacity.
+    pub fn try_from_string(other: String) -> Result<Self, MownStrError> {
+        if other.len() > LEN_MASK as usize {
+            return Err(MownStrError::TooLong(other.len()));
+        }
+        Ok(other.into())
+    }
+
+    /// Convert this `MownStr32` to any type `T`
+    /// that can be created from either a `&str` or a `Box<str>`.
+    ///
+    /// See [`MownStr::to`].
+    #[must_use]
+    pub fn to<T>(mut self) -> T
+    where
+        T: From<&'a str> + From<Box<str>>,
+    {
+        if self.is_owned() {
+            unsafe { self.extract_box() }.into()
+        } else {
+            unsafe { self.make_ref() }.into()
+        }
+    }
+
+    #[inline]
+    const fn real_len(&self) -> usize {
+        (self.xlen & LEN_MASK) as usize
+    }
+
+    #[inline]
+    unsafe fn make_ref(&self) -> &'a str {
+        debug_assert!(self.is_borrowed(), "make_ref() called on owned MownStr32");
+        let slice = slice::from_raw_parts({ self.addr }.as_ptr(), self.real_len());
+        str::from_utf8_unchecked(slice)
+    }
+
+    /// Convert an *owned* `MownStr32` to a box.
+    //
+    // NB: see MownStr::extract_box
+    #[inline]
+    unsafe fn extract_box(&mut self) -> Box<str> {
+        debug_assert!(
+            self.is_owned(),
+            "extract_box() called on borrowed MownStr32"
+        );
+        let ptr = { self.addr }.as_ptr();
+        let len = self.real_len();
+        // turn to borrowed, to avoid double-free
+        self.xlen = 0;
+        let slice = slice::from_raw_parts_mut(ptr, len);
+        let raw = str::from_utf8_unchecked_mut(slice) as *mut str;
+        Box::from_raw(raw)
+    }
+}
+
+impl Drop for MownStr32<'_> {
+    fn drop(&mut self) {
+        if self.is_owned() {
+            drop(unsafe { self.extract_box() });
+        }
+    }
+}
+
+impl Clone for MownStr32<'_> {
+    fn clone(&self) -> Self {
+        if self.is_owned() {
+            Box::<str>::from(&**self).into()
+        } else {
+            MownStr32 {
+                addr: self.addr,
+                xlen: self.xlen,
+                _phd: self._phd,
+            }
+        }
+    }
+}
+
+// Construct a MownStr32
+
+impl<'a> From<&'a str> for MownStr32<'a> {
+    fn from(other: &'a str) -> Self {
+        Self::from_ref(other)
+    }
+}
+
+impl From<Box<str>> for MownStr32<'_> {
+    fn from(other: Box<str>) -> Self {
+        let len = other.len();
+        check_len(len);
+        let addr = Box::leak(other).as_mut_ptr();
+        let addr = unsafe {
+            // SAFETY: ptr can not be null,
+            NonNull::new_unchecked(addr)
+        };
+        MownStr32 {
+            addr,
+            xlen: len as u32 | OWNED,
+            _phd: PhantomData,
+        }
+    }
+}
+
+impl From<String> for MownStr32<'_> {
+    fn from(other: String) -> Self {
+        other.into_boxed_str().into()
+    }
+}
+
+impl<'a> From<Cow<'a, str>> for MownStr32<'a> {
+    fn from(other: Cow<'a, str>) -> Self {
+        match other {
+            Cow::Borrowed(r) => r.into(),
+            Cow::Owned(s) => s.into(),
+        }
+    }
+}
+
+impl<'a> TryFrom<MownStr<'a>> for MownStr32<'a> {
+    type Error = MownStrError;
+
+    /// A borrowed `MownStr` gives a borrowed `MownStr32`;
+    /// an owned `Box<str>` is moved without any copy,
+    /// an owned `String` is shrunk to fit (which may reallocate it),
+    /// while other owned `MownStr`s are copied.
+    fn try_from(other: MownStr<'a>) -> Result<Self, MownStrError> {
+        if other.len() > LEN_MASK as usize {
+            return Err(MownStrError::TooLong(other.len()));
+        }
+        Ok(match other.as_borrowed() {
+            Some(txt) => txt.into(),
+            None => other.to::<Box<str>>().into(),
+        })
+    }
+}
+
+// Using a MownStr32 as a str
+
+impl Deref for MownStr32<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        unsafe {
+            let slice = slice::from_raw_parts({ self.addr }.as_ptr(), self.real_len());
+            str::from_utf8_unchecked(slice)
+        }
+    }
+}
+
+impl AsRef<str> for MownStr32<'_> {
+    fn as_ref(&self) -> &str {
+        self
+    }
+}
+
+impl core::borrow::Borrow<str> for MownStr32<'_> {
+    fn borrow(&self) -> &str {
+        self
+    }
+}
+
+// Comparing between MownStr32
+
+impl hash::Hash for MownStr32<'_> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.deref().hash(state);
+    }
+}
+
+impl<'b> PartialEq<MownStr32<'b>> for MownStr32<'_> {
+    fn eq(&self, other: &MownStr32<'b>) -> bool {
+        **self == **other
+    }
+}
+
+impl Eq for MownStr32<'_> {}
+
+impl<'b> PartialOrd<MownStr32<'b>> for MownStr32<'_> {
+    fn partial_cmp(&self, other: &MownStr32<'b>) -> Option<core::cmp::Ordering> {
+        Some(self.deref().cmp(&**other))
+    }
+}
+
+impl Ord for MownStr32<'_> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.deref().cmp(&**other)
+    }
+}
+
+// Formatting
+
+impl fmt::Debug for MownStr32<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&**self, f)
+    }
+}
+
+impl fmt::Display for MownStr32<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&**self, f)
+    }
+}
+
+// Converting
+
+impl<'a> From<MownStr32<'a>> for Box<str> {
+    fn from(other: MownStr32<'a>) -> Self {
+        other.to()
+    }
+}
+
+impl<'a> From<MownStr32<'a>> for String {
+    fn from(other: MownStr32<'a>) -> Self {
+        other.to()
+    }
+}
+
+impl<'a> From<MownStr32<'a>> for Cow<'a, str> {
+    fn from(other: MownStr32<'a>) -> Self {
+        if other.is_owned() {
+            Cow::Owned(other.into())
+        } else {
+            // SAFETY: other is borrowed
+            Cow::Borrowed(unsafe { other.make_ref() })
+        }
+    }
+}
+
+impl<'a> From<MownStr32<'a>> for MownStr<'a> {
+    /// The data of an owned `MownStr32` is moved without any copy
+    /// (unless it is short enough to be stored inline).
+    fn from(other: MownStr32<'a>) -> Self {
+        if other.is_owned() {
+            let txt = other.to::<Box<str>>();
+            if txt.len() <= super::INLINE_CAP {
+                MownStr::owned(&txt)
+            } else {
+                txt.into()
+            }
+        } else {
+            unsafe { other.make_ref() }.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+    use std::collections::HashSet;
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn size() {
+        assert_eq!(core::mem::size_of::<MownStr32<'static>>(), 12);
+        assert_eq!(core::mem::align_of::<MownStr32<'static>>(), 4);
+        assert_eq!(core::mem::size_of::<[MownStr32<'static>; 4]>(), 48);
+    }
+
+    #[test]
+    fn niche() {
+        assert_eq!(
+            core::mem::size_of::<MownStr32<'static>>(),
+            core::mem::size_of::<Option<MownStr32<'static>>>(),
+        );
+    }
+
+    #[test]
+    fn packed_container() {
+        #[repr(C, packed)]
+        struct Row<'a> {
+            flag: u8,
+            value: MownStr32<'a>,
+        }
+        let row = Row {
+            flag: 1,
+            value: "hello world and everyone".to_string().into(),
+        };
+        assert_eq!(
+            core::mem::size_of::<Row>(),
+            1 + core::mem::size_of::<MownStr32>()
+        );
+        let Row { flag, value } = row;
+        assert_eq!(flag, 1);
+        assert_eq!(value, "hello world and everyone");
+    }
+
+    #[test]
+    fn build_borrowed() {
+        let txt = "hello".to_string();
+        let mown = MownStr32::from(&txt[..]);
+        assert!(mown.is_borrowed());
+        assert_eq!(mown.as_ptr(), txt.as_ptr());
+        assert_eq!(mown, "hello");
+        let mown = MownStr32::from(Cow::Borrowed(&txt[..]));
+        assert!(mown.is_borrowed());
+    }
+
+    #[test]
+    fn build_owned() {
+        let bx: Box<str> = "hello".into();
+        let ptr = bx.as_ptr();
+        let mown = MownStr32::from(bx);
+        assert!(mown.is_owned());
+        assert_eq!(mown.as_ptr(), ptr);
+        assert_eq!(mown, "hello");
+        let mown = MownStr32::from("hello".to_string());
+        assert!(mown.is_owned());
+        let mown = MownStr32::from(Cow::<str>::Owned("hello".to_string()));
+        assert!(mown.is_owned());
+    }
+
+    #[test]
+    fn try_build() {
+        assert!(MownStr32::try_from_ref("hello").unwrap().is_borrowed());
+        assert!(MownStr32::try_from_box("hello".into()).unwrap().is_owned());
+        assert!(MownStr32::try_from_string("hello".into())
+            .unwrap()
+            .is_owned());
+    }
+
+    #[test]
+    #[cfg(all(target_pointer_width = "64", not(miri)))]
+    #[ignore = "allocates 2 GiB; run with `cargo test -- --ignored`"]
+    fn too_long() {
+        let big = unsafe { String::from_utf8_unchecked(alloc::vec![0; 1 << 31]) };
+        assert_eq!(
+            MownStr32::try_from_ref(&big).unwrap_err(),
+            MownStrError::TooLong(1 << 31),
+        );
+        assert!(MownStr32::try_from(MownStr::from(&big[..])).is_err());
+        assert!(MownStr32::try_from_string(big).is_err());
+    }
+
+    #[test]
+    fn clone() {
+        let mown1 = MownStr32::from("hello".to_string());
+        let mown2 = mown1.clone();
+        assert!(mown2.is_owned());
+        assert_ne!(mown1.as_ptr(), mown2.as_ptr());
+        assert_eq!(mown1, mown2);
+
+        let mown1 = MownStr32::from("hello");
+        let mown2 = mown1.clone();
+        assert!(mown2.is_borrowed());
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+    }
+
+    #[test]
+    fn borrowed_and_into_owned() {
+        let mown1 = MownStr32::from("hello".to_string());
+        let mown2 = mown1.borrowed();
+        assert!(mown2.is_borrowed());
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+
+        let txt = "hello".to_string();
+        let mown: MownStr32<'static> = MownStr32::from(&txt[..]).into_owned();
+        drop(txt);
+        assert!(mown.is_owned());
+        assert_eq!(mown, "hello");
+    }
+
+    #[test]
+    fn to() {
+        let mown = MownStr32::from("hello".to_string());
+        let ptr = mown.as_ptr();
+        let bx = Box::<str>::from(mown);
+        assert_eq!(bx.as_ptr(), ptr);
+        assert_eq!(String::from(MownStr32::from("hello")), "hello");
+        assert!(matches!(
+            Cow::from(MownStr32::from("hello")),
+            Cow::Borrowed("hello")
+        ));
+    }
+
+    #[test]
+    fn from_mownstr32() {
+        let mown = MownStr::from(MownStr32::from("hello"));
+        assert!(mown.is_borrowed());
+        let mown = MownStr::from(MownStr32::from("hello".to_string()));
+        assert!(mown.is_owned());
+        let mown32 = MownStr32::from("hello world and everyone".to_string());
+        let ptr = mown32.as_ptr();
+        let mown = MownStr::from(mown32);
+        assert_eq!(mown.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn from_mownstr() {
+        let mown32 = MownStr32::try_from(MownStr::from("hello")).unwrap();
+        assert!(mown32.is_borrowed());
+        let mown = MownStr::from("hello world and everyone".to_string());
+        let ptr = mown.as_ptr();
+        let mown32 = MownStr32::try_from(mown).unwrap();
+        assert!(mown32.is_owned());
+        assert_eq!(mown32.as_ptr(), ptr);
+        let mown32 = MownStr32::try_from(MownStr::from(Arc::<str>::from("hello"))).unwrap();
+        assert!(mown32.is_owned());
+        assert_eq!(mown32, "hello");
+    }
+
+    #[test]
+    #[allow(clippy::cmp_owned)]
+    fn cmp() {
+        let mown32 = MownStr32::from("a");
+        assert_eq!(mown32, MownStr32::from("a".to_string()));
+        assert_eq!(mown32, "a");
+        assert_eq!("a", mown32);
+        assert_eq!(mown32, MownStr::from("a"));
+        assert_eq!(MownStr::from("a"), mown32);
+        assert!(mown32 < MownStr32::from("b"));
+        assert!(mown32 < "b".to_string());
+        assert!(MownStr::from("b") > mown32);
+    }
+
+    #[test]
+    fn hash() {
+        let mut set = HashSet::new();
+        set.insert(MownStr32::from("hello".to_string()));
+        assert!(set.contains("hello"));
+        assert!(set.contains(&MownStr32::from("hello")));
+    }
+
+    #[test]
+    fn display() {
+        let mown = MownStr32::from("hello");
+        assert_eq!(format!("{mown:?}"), "\"hello\"");
+        assert_eq!(format!("{mown}"), "hello");
+    }
+
+    #[test]
+    fn many() {
+        let v: Vec<MownStr32> = (0..100).map(|i| i.to_string().into()).collect();
+        for (i, mown) in v.iter().enumerate() {
+            assert_eq!(&mown[..], i.to_string());
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Copy-on-write mutation of [`MownStr`].
+
+use super::{MownStr, BOXED, INLINE, STRING};
+use alloc::string::String;
+use core::fmt;
+use core::ops::{Deref, DerefMut};
+use core::slice;
+use core::str;
+
+impl<'a> MownStr<'a> {
+    /// Get a mutable handle on the content of this `MownStr`,
+    /// in the form of a [`String`].
+    ///
+    /// A borrowed (or shared) `MownStr` is copied into the `String`,
+    /// while an owned `Box<str>` is moved into it without any copy.
+    /// When the handle is dropped, the content of the `String`
+    /// is moved back into this `MownStr`, which is then owned.
+    ///
+    /// NB: as the `String` uses the global allocator,
+    /// so does this `MownStr` afterwards,
+    /// even if it was allocated in a custom allocator with `new_in`.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let mut ms = MownStr::from("hello");
+    /// ms.to_mut().push_str(" world");
+    /// assert!(ms.is_owned());
+    /// assert_eq!(ms, "hello world");
+    /// ```
+    pub fn to_mut(&mut self) -> MownStrMut<'_, 'a> {
+        let buffer = core::mem::replace(self, MownStr::from_ref("")).into();
+        MownStrMut {
+            target: self,
+            buffer,
+        }
+    }
+
+    /// Get a mutable reference to the underlying `str`.
+    ///
+    /// A borrowed (or shared) `MownStr` is first converted to an owned one,
+    /// by copying the underlying string (in the global allocator,
+    /// even if it was allocated in a custom allocator with `new_in`).
+    ///
+    /// Contrarily to [`to_mut`](MownStr::to_mut),
+    /// this does not allow to change the length of the string,
+    /// but this never moves the data of an owned `MownStr`.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let mut ms = MownStr::from("hello");
+    /// ms.make_mut().make_ascii_uppercase();
+    /// assert!(ms.is_owned());
+    /// assert_eq!(ms, "HELLO");
+    /// ```
+    pub fn make_mut(&mut self) -> &mut str {
+        let tag = self.tag();
+        if tag != BOXED && tag != INLINE && tag != STRING {
+            let owned = MownStr::owned(self);
+            *self = owned;
+        }
+        let len = self.real_len();
+        let ptr = if self.tag() == INLINE {
+            // inline data starts at the second byte of the struct
+            unsafe { (self as *mut Self).cast::<u8>().add(1) }
+        } else {
+            self.addr.as_ptr()
+        };
+        unsafe {
+            // SAFETY: self is exclusively borrowed, and owns its data
+            let slice = slice::from_raw_parts_mut(ptr, len);
+            str::from_utf8_unchecked_mut(slice)
+        }
+    }
+}
+
+/// A mutable handle on the content of a [`MownStr`],
+/// as returned by [`MownStr::to_mut`].
+///
+/// It dereferences to a [`String`],
+/// whose content is written back to the `MownStr` when the handle is dropped.
+pub struct MownStrMut<'m, 'a> {
+    target: &'m mut MownStr<'a>,
+    buffer: String,
+}
+
+impl Deref for MownStrMut<'_, '_> {
+    type Target = String;
+
+    fn deref(&self) -> &String {
+        &self.buffer
+    }
+}
+
+impl DerefMut for MownStrMut<'_, '_> {
+    fn deref_mut(&mut self) -> &mut String {
+        &mut self.buffer
+    }
+}
+
+impl Drop for MownStrMut<'_, '_> {
+    fn drop(&mut self) {
+        *self.target = core::mem::take(&mut self.buffer).into();
+    }
+}
+
+impl fmt::Debug for MownStrMut<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.buffer, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{MownStr, BOXED, INLINE};
+    use std::sync::Arc;
+
+    #[test]
+    fn to_mut_borrowed() {
+        let txt = "hello";
+        let mut mown = MownStr::from(txt);
+        mown.to_mut().push_str(" world and everyone");
+        assert!(mown.is_owned());
+        assert_eq!(mown, "hello world and everyone");
+        assert_eq!(txt, "hello");
+    }
+
+    #[test]
+    fn to_mut_boxed() {
+        let mut mown = MownStr::from("hello world and everyone".to_string());
+        let ptr = mown.as_ptr();
+        {
+            let buffer = mown.to_mut();
+            assert_eq!(buffer.as_ptr(), ptr);
+            assert_eq!(&buffer[..], "hello world and everyone");
+        }
+        assert_eq!(mown.tag(), BOXED);
+        assert_eq!(mown.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn to_mut_shrink_to_inline() {
+        let mut mown = MownStr::from("hello world and everyone".to_string());
+        mown.to_mut().truncate(5);
+        assert_eq!(mown.tag(), INLINE);
+        assert_eq!(mown, "hello");
+    }
+
+    #[test]
+    fn to_mut_shared() {
+        let arc: Arc<str> = "hello".into();
+        let mut mown = MownStr::from(arc.clone());
+        mown.to_mut().push('!');
+        assert!(mown.is_owned());
+        assert!(!mown.is_shared());
+        assert_eq!(mown, "hello!");
+        assert_eq!(&*arc, "hello");
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    #[test]
+    fn make_mut_borrowed() {
+        let txt = "hello";
+        let mut mown = MownStr::from(txt);
+        mown.make_mut().make_ascii_uppercase();
+        assert!(mown.is_owned());
+        assert_eq!(mown, "HELLO");
+        assert_eq!(txt, "hello");
+    }
+
+    #[test]
+    fn make_mut_inline() {
+        let mut mown = MownStr::from("hello".to_string());
+        mown.make_mut().make_ascii_uppercase();
+        assert_eq!(mown.tag(), INLINE);
+        assert_eq!(mown, "HELLO");
+    }
+
+    #[test]
+    fn make_mut_boxed() {
+        let mut mown = MownStr::from("hello world and everyone".to_string());
+        let ptr = mown.as_ptr();
+        mown.make_mut().make_ascii_uppercase();
+        assert_eq!(mown.as_ptr(), ptr);
+        assert_eq!(mown, "HELLO WORLD AND EVERYONE");
+    }
+
+    #[test]
+    fn make_mut_shared() {
+        let arc: Arc<str> = "hello".into();
+        let mut mown = MownStr::from(arc.clone());
+        mown.make_mut().make_ascii_uppercase();
+        assert!(!mown.is_shared());
+        assert_eq!(mown, "HELLO");
+        assert_eq!(&*arc, "hello");
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+}
@@ -0,0 +1,336 @@
+//! Validated newtypes over [`MownStr`](crate::MownStr).
+
+/// Define a newtype over [`MownStr<'a>`](crate::MownStr),
+/// whose content is checked by a validation function.
+///
+/// The validation function must have the signature `fn(&str) -> Result<(), E>`,
+/// where `E` is the error type given after the arrow.
+///
+/// The generated type `Name<'a>`:
+/// * is built with `Name::new`
+///   (accepting anything convertible into a `MownStr<'a>`, and checking it),
+///   `TryFrom<&'a str>`, `TryFrom<String>`, `TryFrom<Box<str>>`, `TryFrom<MownStr<'a>>`,
+///   or `Name::new_unchecked` (which only checks the content in debug mode);
+/// * has the same methods as `MownStr` to change its lifetime and convert it
+///   (`borrowed`, `into_owned`, `to`), and gives access to the underlying `MownStr`
+///   (`as_mown`, `into_mown`);
+/// * derefs to `str`, and implements `Clone`, `Debug`, `Display`, `AsRef<str>`, `Borrow<str>`,
+///   as well as `Hash`, `Eq` and `Ord` (consistently with `str`),
+///   and `PartialEq` with `str`, `&str` and `MownStr`;
+/// * can be converted into a `MownStr<'a>`, a `String` or a `Box<str>`.
+///
+/// # Usage
+/// ```
+/// # use mownstr::{mown_newtype, MownStr};
+/// #[derive(Debug, PartialEq)]
+/// pub struct InvalidWord;
+///
+/// fn check_word(txt: &str) -> Result<(), InvalidWord> {
+///     if !txt.is_empty() && txt.chars().all(char::is_alphabetic) {
+///         Ok(())
+///     } else {
+///         Err(InvalidWord)
+///     }
+/// }
+///
+/// mown_newtype! {
+///     /// A non-empty sequence of letters.
+///     pub struct Word(check_word) -> InvalidWord;
+/// }
+///
+/// let w = Word::new("hello").unwrap();
+/// assert!(w.as_mown().is_borrowed());
+/// assert_eq!(w, "hello");
+/// assert_eq!(Word::try_from("hello world".to_string()), Err(InvalidWord));
+/// let s: String = w.into();
+/// ```
+#[macro_export]
+macro_rules! mown_newtype {
+    (
+        $(#[$attr:meta])*
+        $vis:vis struct $name:ident($validate:path) -> $error:ty;
+    ) => {
+        $(#[$attr])*
+        #[derive(
+            ::core::clone::Clone,
+            ::core::fmt::Debug,
+            ::core::cmp::PartialEq,
+            ::core::cmp::Eq,
+            ::core::cmp::PartialOrd,
+            ::core::cmp::Ord,
+            ::core::hash::Hash,
+        )]
+        $vis struct $name<'a>($crate::MownStr<'a>);
+
+        impl<'a> $name<'a> {
+            /// Build a new value, or fail if `txt` is not valid.
+            pub fn new(
+                txt: impl ::core::convert::Into<$crate::MownStr<'a>>,
+            ) -> ::core::result::Result<Self, $error> {
+                let txt = txt.into();
+                $validate(&txt)?;
+                ::core::result::Result::Ok($name(txt))
+            }
+
+            /// Build a new value without checking that `txt` is valid
+            /// (except in debug mode).
+            ///
+            /// It is a logic error to call this function with an invalid `txt`.
+            #[must_use]
+            pub fn new_unchecked(txt: impl ::core::convert::Into<$crate::MownStr<'a>>) -> Self {
+                let txt = txt.into();
+                ::core::debug_assert!($validate(&txt).is_ok());
+                $name(txt)
+            }
+
+            #[must_use]
+            pub fn as_mown(&self) -> &$crate::MownStr<'a> {
+                &self.0
+            }
+
+            #[must_use]
+            pub fn into_mown(self) -> $crate::MownStr<'a> {
+                self.0
+            }
+
+            #[must_use]
+            pub fn borrowed(&self) -> $name<'_> {
+                $name(self.0.borrowed())
+            }
+
+            // NB: `$crate` is not substituted in doc comments, so they can not link to MownStr
+            /// See `MownStr::into_owned`.
+            #[must_use]
+            pub fn into_owned(self) -> $name<'static> {
+                $name(self.0.into_owned())
+            }
+
+            /// See `MownStr::to`.
+            #[must_use]
+            pub fn to<T>(self) -> T
+            where
+                T: ::core::convert::From<&'a str> + ::core::convert::From<$crate::__private::Box<str>>,
+            {
+                self.0.to()
+            }
+        }
+
+        impl<'a> ::core::convert::TryFrom<$crate::MownStr<'a>> for $name<'a> {
+            type Error = $error;
+
+            fn try_from(other: $crate::MownStr<'a>) -> ::core::result::Result<Self, $error> {
+                Self::new(other)
+            }
+        }
+
+        impl<'a> ::core::convert::TryFrom<&'a str> for $name<'a> {
+            type Error = $error;
+
+            fn try_from(other: &'a str) -> ::core::result::Result<Self, $error> {
+                Self::new(other)
+            }
+        }
+
+        impl ::core::convert::TryFrom<$crate::__private::String> for $name<'_> {
+            type Error = $error;
+
+            fn try_from(other: $crate::__private::String) -> ::core::result::Result<Self, $error> {
+                Self::new(other)
+            }
+        }
+
+        impl ::core::convert::TryFrom<$crate::__private::Box<str>> for $name<'_> {
+            type Error = $error;
+
+            fn try_from(other: $crate::__private::Box<str>) -> ::core::result::Result<Self, $error> {
+                Self::new(other)
+            }
+        }
+
+        impl<'a> ::core::convert::From<$name<'a>> for $crate::MownStr<'a> {
+            fn from(other: $name<'a>) -> Self {
+                other.0
+            }
+        }
+
+        impl<'a> ::core::convert::From<$name<'a>> for $crate::__private::String {
+            fn from(other: $name<'a>) -> Self {
+                other.0.into()
+            }
+        }
+
+        impl<'a> ::core::convert::From<$name<'a>> for $crate::__private::Box<str> {
+            fn from(other: $name<'a>) -> Self {
+                other.0.into()
+            }
+        }
+
+        impl ::core::ops::Deref for $name<'_> {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl ::core::convert::AsRef<str> for $name<'_> {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl ::core::borrow::Borrow<str> for $name<'_> {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl ::core::fmt::Display for $name<'_> {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Display::fmt(&*self.0, f)
+            }
+        }
+
+        impl ::core::cmp::PartialEq<str> for $name<'_> {
+            fn eq(&self, other: &str) -> bool {
+                *self.0 == *other
+            }
+        }
+
+        impl<'b> ::core::cmp::PartialEq<&'b str> for $name<'_> {
+            fn eq(&self, other: &&'b str) -> bool {
+                *self.0 == **other
+            }
+        }
+
+        impl<'b> ::core::cmp::PartialEq<$crate::MownStr<'b>> for $name<'_> {
+            fn eq(&self, other: &$crate::MownStr<'b>) -> bool {
+                *self.0 == **other
+            }
+        }
+    };
+}
+
+/// Items used by [`mown_newtype!`], which must be reachable from other crates
+/// (including `no_std` ones, that do not have `Box` and `String` in their prelude).
+#[doc(hidden)]
+pub mod __private {
+    pub use alloc::boxed::Box;
+    pub use alloc::string::String;
+}
+
+#[cfg(test)]
+mod test {
+    use crate::MownStr;
+    use alloc::string::{String, ToString};
+    use std::collections::HashSet;
+
+    #[derive(Debug, PartialEq)]
+    struct InvalidTag;
+
+    fn check_tag(txt: &str) -> Result<(), InvalidTag> {
+        let valid = !txt.is_empty()
+            && txt.split('-').all(|sub| {
+                (1..=8).contains(&sub.len()) && sub.bytes().all(|b| b.is_ascii_alphanumeric())
+            });
+        if valid {
+            Ok(())
+        } else {
+            Err(InvalidTag)
+        }
+    }
+
+    mown_newtype! {
+        /// A simplified language tag.
+        struct Tag(check_tag) -> InvalidTag;
+    }
+
+    /// Checks that the macro does not depend on the names in scope.
+    mod shadowed {
+        #![allow(dead_code)]
+        type Result<T> = core::result::Result<T, ()>;
+        struct Ok;
+        trait From {}
+        trait AsRef {}
+        trait PartialEq {}
+
+        mown_newtype! {
+            struct Tag(super::check_tag) -> super::InvalidTag;
+        }
+    }
+
+    #[test]
+    fn new() {
+        let txt = "en-GB".to_string();
+        let tag = Tag::new(&txt[..]).unwrap();
+        assert!(tag.as_mown().is_borrowed());
+        assert_eq!(tag.as_ptr(), txt.as_ptr());
+        assert_eq!(tag, "en-GB");
+        assert_eq!(Tag::new("en-"), Err(InvalidTag));
+        assert_eq!(Tag::new(MownStr::from("toolongsubtag")), Err(InvalidTag));
+    }
+
+    #[test]
+    fn try_from() {
+        assert!(Tag::try_from("fr").unwrap().as_mown().is_borrowed());
+        assert!(Tag::try_from("fr".to_string())
+            .unwrap()
+            .as_mown()
+            .is_owned());
+        assert!(Tag::try_from(Box::<str>::from("fr")).is_ok());
+        assert!(Tag::try_from(MownStr::from("fr")).is_ok());
+        assert_eq!(Tag::try_from(""), Err(InvalidTag));
+    }
+
+    #[test]
+    fn new_unchecked() {
+        let tag = Tag::new_unchecked("fr");
+        assert_eq!(tag, "fr");
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn new_unchecked_invalid() {
+        let _ = Tag::new_unchecked("not a tag");
+    }
+
+    #[test]
+    fn lifetimes() {
+        let txt = "en-GB".to_string();
+        let tag: Tag<'static> = {
+            let tag1 = Tag::new(&txt[..]).unwrap();
+            let tag2 = tag1.borrowed();
+            assert_eq!(tag1, tag2);
+            tag2.into_owned()
+        };
+        drop(txt);
+        assert!(tag.as_mown().is_owned());
+        assert_eq!(tag, "en-GB");
+    }
+
+    #[test]
+    fn conversions() {
+        let tag = Tag::new("en-GB-oxendict-x-private".to_string()).unwrap();
+        let ptr = tag.as_ptr();
+        assert_eq!(tag.clone().to::<String>(), "en-GB-oxendict-x-private");
+        assert_eq!(String::from(tag.clone()), "en-GB-oxendict-x-private");
+        assert_eq!(MownStr::from(tag.clone()), "en-GB-oxendict-x-private");
+        assert_eq!(tag.clone().into_mown(), "en-GB-oxendict-x-private");
+        let bx = Box::<str>::from(tag);
+        assert_eq!(bx.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn traits() {
+        let tag = Tag::new("en").unwrap();
+        assert_eq!(tag.to_string(), "en");
+        assert_eq!(format!("{tag:?}"), "Tag(\"en\")");
+        assert_eq!(tag, MownStr::from("en"));
+        assert!(tag < Tag::new("fr").unwrap());
+        assert_eq!(tag.len(), 2);
+        let mut set = HashSet::new();
+        set.insert(tag);
+        assert!(set.contains("en"));
+    }
+}
@@ -0,0 +1,316 @@
+//! Owned [`MownStr`] backed by an arbitrary owner.
+
+use super::{check_len, CustomHeader, MownStr, CUSTOM, LEN_MASK, SHARED, SHARED_SLICE};
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::ops::Range;
+use core::ptr::NonNull;
+use core::sync::atomic::AtomicUsize;
+
+/// The layout of a custom string backed by an owner of type `O`.
+#[repr(C)]
+struct WithOwner<O> {
+    header: CustomHeader,
+    owner: O,
+}
+
+unsafe fn drop_owner<O>(header: NonNull<CustomHeader>, _: usize) {
+    drop(Box::from_raw(header.cast::<WithOwner<O>>().as_ptr()));
+}
+
+/// Frees a `WithOwner` if `from_owner` panics before the `MownStr` is built.
+struct Guard<O>(*mut WithOwner<O>);
+
+impl<O> Drop for Guard<O> {
+    fn drop(&mut self) {
+        drop(unsafe { Box::from_raw(self.0) });
+    }
+}
+
+/// Build a shared slice (see `SHARED_SLICE`) of `root`.
+fn shared_slice(root: MownStr<'static>, range: Range<usize>) -> MownStr<'static> {
+    debug_assert!(matches!(root.tag(), SHARED | CUSTOM));
+    let mut mown = MownStr::from_owner(root, move |root| &root[range]);
+    mown.xlen = (mown.xlen & LEN_MASK) | SHARED_SLICE;
+    mown
+}
+
+impl MownStr<'_> {
+    /// Build an owned `MownStr` from `owner`,
+    /// pointing to the string returned by `f` (which must be borrowed from `owner`).
+    ///
+    /// This allows to use any storage (e.g. a memory-mapped file, or a buffer allocated by a C library)
+    /// as an owned `MownStr`, without copying the string.
+    /// The owner is moved to the heap, and kept alive until the `MownStr` is dropped.
+    ///
+    /// The resulting `MownStr` is [shared](MownStr::is_shared):
+    /// cloning it does not copy the string,
+    /// and the owner is dropped when the last clone is dropped.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let bytes: Vec<u8> = b"key=value".to_vec();
+    /// let ms = MownStr::from_owner(bytes, |b| {
+    ///     let txt = std::str::from_utf8(b).unwrap();
+    ///     txt.split('=').nth(1).unwrap()
+    /// });
+    /// assert!(ms.is_owned());
+    /// assert_eq!(ms, "value");
+    /// ```
+    pub fn from_owner<O, F>(owner: O, f: F) -> Self
+    where
+        O: Send + Sync + 'static,
+        F: FnOnce(&O) -> &str,
+    {
+        let inner = Box::into_raw(Box::new(WithOwner {
+            header: CustomHeader {
+                count: AtomicUsize::new(1),
+                data: NonNull::dangling(),
+                drop: drop_owner::<O>,
+            },
+            owner,
+        }));
+        let guard = Guard(inner);
+        // SAFETY: inner is a valid pointer, that no one else is using
+        let txt = f(unsafe { &(*inner).owner });
+        let (data, len) = (NonNull::from(txt.as_bytes()).cast(), txt.len());
+        check_len(len);
+        core::mem::forget(guard);
+        unsafe {
+            // SAFETY: the owner (hence data) is not moved nor modified
+            // until the header is dropped
+            (*inner).header.data = data;
+            MownStr::from_custom(NonNull::new_unchecked(inner).cast(), len)
+        }
+    }
+
+    /// Convert this `MownStr` into a [shared](MownStr::is_shared) one.
+    ///
+    /// Shared `MownStr`s can be cloned, [sliced](MownStr::into_slice) and
+    /// [split](MownStr::split_mown) without copying the underlying string.
+    /// If this `MownStr` is not already shared, its content is copied into an `Arc<str>`.
+    ///
+    /// Note however that each shared substring
+    /// (unless it is short enough to be stored inline)
+    /// requires a small heap allocation (a few words),
+    /// keeping track of the shared data.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let doc = MownStr::from("hello world and everyone").into_shared();
+    /// let ptr = doc.as_ptr();
+    /// let sub = doc.into_slice(6..);
+    /// assert!(sub.is_shared());
+    /// assert_eq!(sub.as_ptr(), ptr.wrapping_add(6));
+    /// ```
+    #[must_use]
+    pub fn into_shared(self) -> MownStr<'static> {
+        if self.is_shared() {
+            self.into_owned()
+        } else {
+            Arc::<str>::from(&*self).into()
+        }
+    }
+
+    /// Build a substring of this shared `MownStr`, sharing its data.
+    pub(crate) fn into_shared_slice(self, range: Range<usize>) -> MownStr<'static> {
+        debug_assert!(self.is_shared());
+        if self.tag() == SHARED_SLICE {
+            // share the root string, rather than this slice
+            // SAFETY: the header of a shared slice is a WithOwner<MownStr>
+            let root =
+                unsafe { &(*self.custom_header().cast::<WithOwner<MownStr>>().as_ptr()).owner };
+            let start = self.as_ptr() as usize - root.as_ptr() as usize;
+            shared_slice(root.clone(), start + range.start..start + range.end)
+        } else {
+            shared_slice(self.into_owned(), range)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WithOwner;
+    use crate::{MownStr, SHARED_SLICE};
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// An owner counting how many times it has been dropped.
+    struct Owner {
+        txt: [u8; 32],
+        drops: Arc<AtomicUsize>,
+    }
+
+    impl Owner {
+        fn new() -> (Self, Arc<AtomicUsize>) {
+            let drops = Arc::new(AtomicUsize::new(0));
+            let mut txt = [b'.'; 32];
+            txt[..24].copy_from_slice(b"hello world and everyone");
+            let owner = Owner {
+                txt,
+                drops: drops.clone(),
+            };
+            (owner, drops)
+        }
+
+        fn get(&self) -> &str {
+            std::str::from_utf8(&self.txt[..24]).unwrap()
+        }
+    }
+
+    impl Drop for Owner {
+        fn drop(&mut self) {
+            self.drops.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn from_owner() {
+        let (owner, drops) = Owner::new();
+        let mown = MownStr::from_owner(owner, |o| &o.get()[6..]);
+        assert!(mown.is_owned());
+        assert!(mown.is_shared());
+        assert_eq!(mown, "world and everyone");
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        drop(mown);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn from_owner_heap() {
+        let txt = "hello world and everyone".to_string();
+        let ptr = txt.as_ptr();
+        let mown = MownStr::from_owner(txt, |t| &t[..]);
+        assert_eq!(mown.as_ptr(), ptr);
+        assert_eq!(mown, "hello world and everyone");
+    }
+
+    #[test]
+    fn clone_owner() {
+        let (owner, drops) = Owner::new();
+        let mown1 = MownStr::from_owner(owner, Owner::get);
+        let mown2 = mown1.clone();
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+        drop(mown1);
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        assert_eq!(mown2, "hello world and everyone");
+        let mown3 = mown2.clone().into_owned();
+        drop(mown2);
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        drop(mown3);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn owner_to_string() {
+        let (owner, drops) = Owner::new();
+        let mown = MownStr::from_owner(owner, Owner::get);
+        let txt = String::from(mown);
+        assert_eq!(txt, "hello world and everyone");
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn send_owner() {
+        let (owner, drops) = Owner::new();
+        let mown = MownStr::from_owner(owner, Owner::get);
+        let mown2 = mown.clone();
+        std::thread::spawn(move || assert_eq!(mown2, "hello world and everyone"))
+            .join()
+            .unwrap();
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        drop(mown);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn into_shared() {
+        let mown = MownStr::from("hello").into_shared();
+        assert!(mown.is_shared());
+        assert_eq!(mown, "hello");
+        let (owner, _) = Owner::new();
+        let mown1 = MownStr::from_owner(owner, Owner::get);
+        let mown2 = mown1.clone().into_shared();
+        assert_eq!(mown1.as_ptr(), mown2.as_ptr());
+    }
+
+    #[test]
+    fn shared_slice_of_custom() {
+        let (owner, drops) = Owner::new();
+        let mown = MownStr::from_owner(owner, Owner::get);
+        let ptr = mown.as_ptr();
+        let sub1 = mown.into_slice(6..);
+        let sub2 = sub1.clone().into_slice(..15);
+        drop(sub1);
+        assert!(sub2.is_shared());
+        assert_eq!(sub2, "world and every");
+        assert_eq!(sub2.as_ptr(), ptr.wrapping_add(6));
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        drop(sub2);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn shared_slice_of_slice() {
+        let arc = Arc::<str>::from("hello world and everyone, all together");
+        let mown = MownStr::from(arc.clone());
+        let sub1 = mown.into_slice(6..);
+        assert_eq!(sub1.tag(), SHARED_SLICE);
+        assert_eq!(Arc::strong_count(&arc), 2);
+        let sub2 = sub1.clone().into_slice(..25);
+        let sub3 = sub2.clone().into_slice(10..);
+        assert_eq!(sub2.tag(), SHARED_SLICE);
+        assert_eq!(sub3.tag(), SHARED_SLICE);
+        // sub2 and sub3 hold the Arc directly, rather than the header of the previous slice
+        assert_eq!(Arc::strong_count(&arc), 4);
+        drop(sub1);
+        drop(sub2);
+        assert_eq!(Arc::strong_count(&arc), 2);
+        assert_eq!(sub3, "everyone, all t");
+        assert_eq!(sub3.as_ptr(), arc.as_ptr().wrapping_add(16));
+        drop(sub3);
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    /// The address of the string owning a shared slice.
+    fn root_of(slice: &MownStr) -> *const u8 {
+        assert_eq!(slice.tag(), SHARED_SLICE);
+        let header = slice.custom_header().cast::<WithOwner<MownStr>>();
+        unsafe { (*header.as_ptr()).owner.addr.as_ptr() }
+    }
+
+    #[test]
+    fn shared_slice_of_custom_slice() {
+        let (owner, drops) = Owner::new();
+        let mown = MownStr::from_owner(owner, Owner::get);
+        let root = mown.addr.as_ptr();
+        let ptr = mown.as_ptr();
+        let sub1 = mown.into_slice(1..);
+        let sub2 = sub1.clone().into_slice(1..);
+        let sub3 = sub2.clone().into_slice(1..);
+        // all slices are owned by the original string, not by each other
+        assert_eq!(root_of(&sub1), root);
+        assert_eq!(root_of(&sub2), root);
+        assert_eq!(root_of(&sub3), root);
+        drop(sub1);
+        drop(sub2);
+        assert_eq!(sub3, "lo world and everyone");
+        assert_eq!(sub3.as_ptr(), ptr.wrapping_add(3));
+        assert_eq!(drops.load(Ordering::SeqCst), 0);
+        drop(sub3);
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn from_owner_panic() {
+        let (owner, drops) = Owner::new();
+        let res = catch_unwind(AssertUnwindSafe(|| {
+            MownStr::from_owner(owner, |_| -> &str { panic!("oops") })
+        }));
+        assert!(res.is_err());
+        assert_eq!(drops.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,123 @@
+//! Implementation of [`serde`] traits for [`MownStr`],
+//! available with the feature `serde`.
+
+use super::MownStr;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str;
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+impl Serialize for MownStr<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self)
+    }
+}
+
+/// Deserializing a `MownStr` borrows from the input whenever the deserializer allows it
+/// (e.g. `serde_json::from_str` on a string without escape sequences),
+/// and falls back to an owned `MownStr` otherwise.
+///
+/// NB: as a consequence, `MownStr` does not implement `DeserializeOwned`;
+/// [`MownStr::into_owned`] can be used to detach the result from the input.
+impl<'de> Deserialize<'de> for MownStr<'de> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(MownStrVisitor)
+    }
+}
+
+struct MownStrVisitor;
+
+impl<'de> Visitor<'de> for MownStrVisitor {
+    type Value = MownStr<'de>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a string")
+    }
+
+    fn visit_borrowed_str<E: de::Error>(self, v: &'de str) -> Result<Self::Value, E> {
+        Ok(MownStr::from_ref(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        Ok(MownStr::owned(v))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Self::Value, E> {
+        Ok(v.into())
+    }
+
+    fn visit_borrowed_bytes<E: de::Error>(self, v: &'de [u8]) -> Result<Self::Value, E> {
+        str::from_utf8(v)
+            .map(MownStr::from_ref)
+            .map_err(|_| E::invalid_value(de::Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        str::from_utf8(v)
+            .map(MownStr::owned)
+            .map_err(|_| E::invalid_value(de::Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        String::from_utf8(v)
+            .map(MownStr::from)
+            .map_err(|e| E::invalid_value(de::Unexpected::Bytes(e.as_bytes()), &self))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::MownStr;
+    use serde::Deserialize;
+
+    #[test]
+    fn serialize() {
+        let mown1 = MownStr::from("hello");
+        let mown2 = MownStr::from("hello world and everyone".to_string());
+        assert_eq!(serde_json::to_string(&mown1).unwrap(), r#""hello""#);
+        assert_eq!(
+            serde_json::to_string(&mown2).unwrap(),
+            r#""hello world and everyone""#
+        );
+    }
+
+    #[test]
+    fn deserialize_borrowed() {
+        let json = r#"["hello", "world"]"#;
+        let v: Vec<MownStr> = serde_json::from_str(json).unwrap();
+        assert_eq!(v, ["hello", "world"]);
+        assert!(v.iter().all(MownStr::is_borrowed));
+        assert!(json.as_bytes().as_ptr_range().contains(&v[0].as_ptr()));
+    }
+
+    #[test]
+    fn deserialize_escaped() {
+        let v: MownStr = serde_json::from_str(r#""hello\nworld""#).unwrap();
+        assert!(v.is_owned());
+        assert_eq!(v, "hello\nworld");
+    }
+
+    #[test]
+    fn deserialize_from_reader() {
+        let json = r#""hello world and everyone""#;
+        let mut de = serde_json::Deserializer::from_reader(json.as_bytes());
+        let v = MownStr::deserialize(&mut de).unwrap();
+        assert!(v.is_owned());
+        assert_eq!(v, "hello world and everyone");
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert!(serde_json::from_str::<MownStr>("42").is_err());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mown1 = MownStr::from("hello \"world\"".to_string());
+        let json = serde_json::to_string(&mown1).unwrap();
+        let mown2: MownStr = serde_json::from_str(&json).unwrap();
+        assert_eq!(mown1, mown2);
+    }
+}
@@ -0,0 +1,362 @@
+//! Splitting [`MownStr`] into pieces that are themselves `MownStr`.
+//!
+//! The iterators of this module are returned by
+//! [`split_mown`](MownStr::split_mown), [`splitn_mown`](MownStr::splitn_mown),
+//! [`split_terminator_mown`](MownStr::split_terminator_mown),
+//! [`lines_mown`](MownStr::lines_mown)
+//! and [`split_whitespace_mown`](MownStr::split_whitespace_mown).
+//! Contrarily to their counterparts in `str`,
+//! they consume the `MownStr` and yield `MownStr<'a>`,
+//! so that the pieces can outlive the `MownStr` they come from:
+//! * if the `MownStr` is borrowed,
+//!   the pieces are borrowed from the same source;
+//! * if the `MownStr` is [shared](MownStr::is_shared),
+//!   the pieces share its data (except for short pieces, which are stored inline),
+//!   although each of them requires a small heap allocation
+//!   (see [`into_shared`](MownStr::into_shared));
+//! * otherwise, each piece is an owned copy.
+//!
+//! # Usage
+//! ```
+//! # use mownstr::MownStr;
+//! fn words(txt: &str) -> Vec<MownStr> {
+//!     MownStr::from(txt).split_whitespace_mown().collect()
+//! }
+//! let v = words("hello  world");
+//! assert_eq!(v, ["hello", "world"]);
+//! assert!(v[0].is_borrowed());
+//! ```
+
+use super::{MownStr, INLINE, INLINE_CAP};
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::iter::FusedIterator;
+use core::str;
+
+/// The patterns accepted by [`split_mown`](MownStr::split_mown) and its variants,
+/// as well as by [`strip_prefix_mown`](MownStr::strip_prefix_mown),
+/// [`strip_suffix_mown`](MownStr::strip_suffix_mown)
+/// and [`replace_mown`](MownStr::replace_mown).
+///
+/// This is implemented by the same types as the (unstable) `Pattern` trait of the standard library:
+/// `char`, `&str`, `&String`, `[char; N]`, `&[char; N]`, `&[char]`,
+/// and closures `FnMut(char) -> bool`.
+pub trait Pattern: private::Sealed + Sized {
+    #[doc(hidden)]
+    type Split<'s>: Iterator<Item = &'s str>;
+    #[doc(hidden)]
+    type SplitN<'s>: Iterator<Item = &'s str>;
+    #[doc(hidden)]
+    type SplitTerminator<'s>: Iterator<Item = &'s str>;
+
+    #[doc(hidden)]
+    fn split(self, txt: &str) -> Self::Split<'_>;
+    #[doc(hidden)]
+    fn splitn(self, txt: &str, n: usize) -> Self::SplitN<'_>;
+    #[doc(hidden)]
+    fn split_terminator(self, txt: &str) -> Self::SplitTerminator<'_>;
+    #[doc(hidden)]
+    fn strip_prefix(self, txt: &str) -> Option<&str>;
+    #[doc(hidden)]
+    fn strip_suffix(self, txt: &str) -> Option<&str>;
+}
+
+mod private {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_pattern {
+    ([$($gen:tt)*] $pat:ty) => {
+        impl<$($gen)*> private::Sealed for $pat {}
+
+        impl<$($gen)*> Pattern for $pat {
+            type Split<'s> = str::Split<'s, $pat>;
+            type SplitN<'s> = str::SplitN<'s, $pat>;
+            type SplitTerminator<'s> = str::SplitTerminator<'s, $pat>;
+
+            fn split(self, txt: &str) -> Self::Split<'_> {
+                txt.split(self)
+            }
+
+            fn splitn(self, txt: &str, n: usize) -> Self::SplitN<'_> {
+                txt.splitn(n, self)
+            }
+
+            fn split_terminator(self, txt: &str) -> Self::SplitTerminator<'_> {
+                txt.split_terminator(self)
+            }
+
+            fn strip_prefix(self, txt: &str) -> Option<&str> {
+                txt.strip_prefix(self)
+            }
+
+            fn strip_suffix(self, txt: &str) -> Option<&str> {
+                txt.strip_suffix(self)
+            }
+        }
+    };
+}
+
+impl_pattern!([] char);
+impl_pattern!(['p] &'p str);
+impl_pattern!(['p] &'p String);
+impl_pattern!([const N: usize] [char; N]);
+impl_pattern!(['p, const N: usize] &'p [char; N]);
+impl_pattern!(['p] &'p [char]);
+impl_pattern!([F: FnMut(char) -> bool] F);
+
+/// An iterator over the pieces of a [`MownStr`],
+/// each of them being a `MownStr` (see the [module documentation](self)).
+pub struct Pieces<'a, I> {
+    // NB: inner must be declared (hence dropped) before source,
+    // as it may point to the data owned by source.
+    inner: I,
+    source: MownStr<'a>,
+}
+
+/// The iterator returned by [`split_mown`](MownStr::split_mown).
+pub type Split<'a, P> = Pieces<'a, <P as Pattern>::Split<'a>>;
+/// The iterator returned by [`splitn_mown`](MownStr::splitn_mown).
+pub type SplitN<'a, P> = Pieces<'a, <P as Pattern>::SplitN<'a>>;
+/// The iterator returned by [`split_terminator_mown`](MownStr::split_terminator_mown).
+pub type SplitTerminator<'a, P> = Pieces<'a, <P as Pattern>::SplitTerminator<'a>>;
+/// The iterator returned by [`lines_mown`](MownStr::lines_mown).
+pub type Lines<'a> = Pieces<'a, str::Lines<'a>>;
+/// The iterator returned by [`split_whitespace_mown`](MownStr::split_whitespace_mown).
+pub type SplitWhitespace<'a> = Pieces<'a, str::SplitWhitespace<'a>>;
+
+impl<'a, I> Pieces<'a, I> {
+    fn new(source: MownStr<'a>, f: impl FnOnce(&'a str) -> I) -> Self {
+        // inline data would move with the iterator, so it is moved to the heap
+        let source = if source.tag() == INLINE {
+            Box::<str>::from(&*source).into()
+        } else {
+            source
+        };
+        // SAFETY:
+        // - if source is borrowed, its data actually lives for 'a;
+        // - if source is owned, its data is on the heap, so it does not move with source,
+        //   and it is not freed before inner is dropped (see the definition of Pieces);
+        //   the pieces yielded by inner are copied, or shared with source, before being returned,
+        //   so that no reference to that data ever escapes
+        let txt = unsafe { &*(&*source as *const str) };
+        Pieces {
+            inner: f(txt),
+            source,
+        }
+    }
+
+    fn make_piece(&self, piece: &'a str) -> MownStr<'a> {
+        if self.source.is_borrowed() {
+            MownStr::from_ref(piece)
+        } else if self.source.is_shared() && piece.len() > INLINE_CAP {
+            let start = piece.as_ptr() as usize - self.source.as_ptr() as usize;
+            let range = start..start + piece.len();
+            self.source.clone().into_shared_slice(range)
+        } else {
+            MownStr::owned(piece)
+        }
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a str>> Iterator for Pieces<'a, I> {
+    type Item = MownStr<'a>;
+
+    fn next(&mut self) -> Option<MownStr<'a>> {
+        let piece = self.inner.next()?;
+        Some(self.make_piece(piece))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, I: DoubleEndedIterator<Item = &'a str>> DoubleEndedIterator for Pieces<'a, I> {
+    fn next_back(&mut self) -> Option<MownStr<'a>> {
+        let piece = self.inner.next_back()?;
+        Some(self.make_piece(piece))
+    }
+}
+
+impl<'a, I: FusedIterator<Item = &'a str>> FusedIterator for Pieces<'a, I> {}
+
+impl<'a> MownStr<'a> {
+    /// Split this `MownStr` by `pat`, as [`str::split`] does,
+    /// yielding `MownStr<'a>` (see the [module documentation](crate::split)).
+    ///
+    /// NB: splitting a [shared](MownStr::is_shared) `MownStr` does not copy the pieces,
+    /// but allocates a small header for each of them (unless they are stored inline).
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let txt = "a,b,c".to_string();
+    /// let v: Vec<MownStr> = MownStr::from(&txt).split_mown(',').collect();
+    /// assert_eq!(v, ["a", "b", "c"]);
+    /// assert!(v[0].is_borrowed());
+    /// ```
+    pub fn split_mown<P: Pattern>(self, pat: P) -> Split<'a, P> {
+        Pieces::new(self, |txt| pat.split(txt))
+    }
+
+    /// Split this `MownStr` by `pat`, at most `n` times, as [`str::splitn`] does,
+    /// yielding `MownStr<'a>` (see the [module documentation](crate::split)).
+    pub fn splitn_mown<P: Pattern>(self, n: usize, pat: P) -> SplitN<'a, P> {
+        Pieces::new(self, |txt| pat.splitn(txt, n))
+    }
+
+    /// Split this `MownStr` by `pat`, as [`str::split_terminator`] does,
+    /// yielding `MownStr<'a>` (see the [module documentation](crate::split)).
+    pub fn split_terminator_mown<P: Pattern>(self, pat: P) -> SplitTerminator<'a, P> {
+        Pieces::new(self, |txt| pat.split_terminator(txt))
+    }
+
+    /// Split this `MownStr` into lines, as [`str::lines`] does,
+    /// yielding `MownStr<'a>` (see the [module documentation](crate::split)).
+    pub fn lines_mown(self) -> Lines<'a> {
+        Pieces::new(self, str::lines)
+    }
+
+    /// Split this `MownStr` by whitespace, as [`str::split_whitespace`] does,
+    /// yielding `MownStr<'a>` (see the [module documentation](crate::split)).
+    pub fn split_whitespace_mown(self) -> SplitWhitespace<'a> {
+        Pieces::new(self, str::split_whitespace)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    fn borrowed_from<'a>(v: &[MownStr<'a>], txt: &str) -> bool {
+        let range = txt.as_bytes().as_ptr_range();
+        v.iter()
+            .all(|m| m.is_borrowed() && range.contains(&m.as_ptr()) || m.is_empty())
+    }
+
+    #[test]
+    fn split_borrowed() {
+        let txt = "a,b,,c".to_string();
+        let v: Vec<_> = {
+            let mown = MownStr::from(&txt);
+            mown.split_mown(',').collect()
+        };
+        assert_eq!(v, ["a", "b", "", "c"]);
+        assert!(borrowed_from(&v, &txt));
+    }
+
+    #[test]
+    fn split_owned() {
+        let txt = "hello world and everyone, and everything else";
+        let mown = MownStr::from(txt.to_string());
+        let v: Vec<MownStr<'static>> = mown.split_mown(", ").collect();
+        assert_eq!(v, ["hello world and everyone", "and everything else"]);
+        assert!(v.iter().all(MownStr::is_owned));
+    }
+
+    #[test]
+    fn split_inline() {
+        let mown = MownStr::from("a:b:c".to_string());
+        let mut it = mown.split_mown(':');
+        let first = it.next().unwrap();
+        let it2 = it; // moving the iterator must not invalidate its content
+        let v: Vec<_> = it2.collect();
+        assert_eq!(first, "a");
+        assert_eq!(v, ["b", "c"]);
+        assert!(v.iter().all(MownStr::is_owned));
+    }
+
+    #[test]
+    fn split_shared() {
+        let arc: Arc<str> = "a b c".into();
+        let v: Vec<_> = MownStr::from(arc.clone()).split_mown(' ').collect();
+        assert_eq!(v, ["a", "b", "c"]);
+        assert!(v.iter().all(MownStr::is_owned));
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    #[test]
+    fn split_shared_long() {
+        let arc: Arc<str> = "hello world and everyone, and everything else".into();
+        let v: Vec<_> = MownStr::from(arc.clone()).split_mown(", ").collect();
+        assert_eq!(v, ["hello world and everyone", "and everything else"]);
+        assert!(v.iter().all(MownStr::is_shared));
+        assert_eq!(v[0].as_ptr(), arc.as_ptr());
+        assert_eq!(v[1].as_ptr(), arc.as_ptr().wrapping_add(26));
+        assert_eq!(Arc::strong_count(&arc), 3);
+        drop(v);
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    #[test]
+    fn split_patterns() {
+        let txt = "a,b;c";
+        let mown = MownStr::from(txt);
+        let sep = ",".to_string();
+        assert_eq!(mown.clone().split_mown(&sep).count(), 2);
+        assert_eq!(mown.clone().split_mown(&sep[..]).count(), 2);
+        assert_eq!(mown.clone().split_mown([',', ';']).count(), 3);
+        assert_eq!(mown.clone().split_mown(&[',', ';']).count(), 3);
+        assert_eq!(mown.clone().split_mown(&[',', ';'][..]).count(), 3);
+        assert_eq!(mown.split_mown(|c: char| !c.is_alphabetic()).count(), 3);
+    }
+
+    #[test]
+    fn split_rev() {
+        let v: Vec<_> = MownStr::from("a,b,c").split_mown(',').rev().collect();
+        assert_eq!(v, ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn splitn() {
+        let txt = "a,b,c";
+        let v: Vec<_> = MownStr::from(txt).splitn_mown(2, ',').collect();
+        assert_eq!(v, ["a", "b,c"]);
+        assert!(borrowed_from(&v, txt));
+        let v: Vec<_> = MownStr::from(txt.to_string()).splitn_mown(2, ',').collect();
+        assert_eq!(v, ["a", "b,c"]);
+        assert!(v.iter().all(MownStr::is_owned));
+    }
+
+    #[test]
+    fn split_terminator() {
+        let txt = "a;b;";
+        let v: Vec<_> = MownStr::from(txt).split_terminator_mown(';').collect();
+        assert_eq!(v, ["a", "b"]);
+        assert!(borrowed_from(&v, txt));
+    }
+
+    #[test]
+    fn lines() {
+        let txt = "hello\r\nworld\n\nand everyone\n";
+        let v: Vec<_> = MownStr::from(txt).lines_mown().collect();
+        assert_eq!(v, ["hello", "world", "", "and everyone"]);
+        assert!(borrowed_from(&v, txt));
+        let v: Vec<_> = MownStr::from(txt.to_string()).lines_mown().collect();
+        assert_eq!(v, ["hello", "world", "", "and everyone"]);
+        assert!(v.iter().all(MownStr::is_owned));
+    }
+
+    #[test]
+    fn split_whitespace() {
+        let txt = "  hello\tworld \n";
+        let v: Vec<_> = MownStr::from(txt).split_whitespace_mown().collect();
+        assert_eq!(v, ["hello", "world"]);
+        assert!(borrowed_from(&v, txt));
+        let v: Vec<_> = MownStr::from(txt.to_string())
+            .split_whitespace_mown()
+            .collect();
+        assert_eq!(v, ["hello", "world"]);
+        assert!(v.iter().all(MownStr::is_owned));
+    }
+
+    #[test]
+    fn split_empty() {
+        let v: Vec<_> = MownStr::from(String::new()).split_mown(',').collect();
+        assert_eq!(v, [""]);
+        assert_eq!(MownStr::from("").split_whitespace_mown().count(), 0);
+    }
+}
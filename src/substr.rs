@@ -0,0 +1,261 @@
+//! Substrings of [`MownStr`].
+
+use super::{MownStr, BOXED, INLINE_CAP, STRING};
+use alloc::string::String;
+use core::ops::{Bound, Range, RangeBounds};
+
+/// Convert `range` to a `Range` in a string of length `len`,
+/// or return `None` if the bounds overflow.
+///
+/// NB: the returned range is *not* checked against `len`.
+fn to_range(range: impl RangeBounds<usize>, len: usize) -> Option<Range<usize>> {
+    let start = match range.start_bound() {
+        Bound::Included(&i) => i,
+        Bound::Excluded(&i) => i.checked_add(1)?,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&i) => i.checked_add(1)?,
+        Bound::Excluded(&i) => i,
+        Bound::Unbounded => len,
+    };
+    Some(start..end)
+}
+
+/// Same as `to_range`, but panics if the bounds overflow.
+fn to_range_or_panic(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+    to_range(range, len).expect("attempted to index str out of bounds")
+}
+
+impl<'a> MownStr<'a> {
+    /// Get a substring of this `MownStr`, borrowed from it.
+    ///
+    /// # Panic
+    /// Panics in the same situations as indexing a `str` with `range`,
+    /// i.e. if the bounds are out of range, or not on char boundaries.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let ms = MownStr::from("hello world".to_string());
+    /// let sub = ms.slice(6..);
+    /// assert!(sub.is_borrowed());
+    /// assert_eq!(sub, "world");
+    /// ```
+    #[must_use]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> MownStr<'_> {
+        let range = to_range_or_panic(range, self.len());
+        MownStr::from_ref(&(**self)[range])
+    }
+
+    /// Get a substring of this `MownStr`, or `None` if `range` is invalid.
+    ///
+    /// This is the non-panicking version of [`slice`](MownStr::slice).
+    #[must_use]
+    pub fn get_mown(&self, range: impl RangeBounds<usize>) -> Option<MownStr<'_>> {
+        let range = to_range(range, self.len())?;
+        (**self).get(range).map(MownStr::from_ref)
+    }
+
+    /// Divide this `MownStr` in two at index `mid`,
+    /// returning two substrings borrowed from it.
+    ///
+    /// # Panic
+    /// Panics if `mid` is past the end of the string, or not on a char boundary.
+    #[must_use]
+    pub fn split_at_mown(&self, mid: usize) -> (MownStr<'_>, MownStr<'_>) {
+        let (left, right) = (**self).split_at(mid);
+        (MownStr::from_ref(left), MownStr::from_ref(right))
+    }
+
+    /// Convert this `MownStr` into one of its substrings,
+    /// preserving its lifetime.
+    ///
+    /// A borrowed `MownStr` remains borrowed (from the same source),
+    /// a [shared](MownStr::is_shared) `MownStr` remains shared (without copying the substring,
+    /// unless it is short enough to be stored inline),
+    /// while an exclusively owned `MownStr` is shrunk (in place if possible) or copied.
+    ///
+    /// NB: a shared substring still requires a small heap allocation
+    /// (see [`into_shared`](MownStr::into_shared)).
+    ///
+    /// # Panic
+    /// Panics in the same situations as [`slice`](MownStr::slice).
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// fn first_word(ms: MownStr) -> MownStr {
+    ///     let end = ms.find(' ').unwrap_or(ms.len());
+    ///     ms.into_slice(..end)
+    /// }
+    /// let txt = "hello world";
+    /// let word: MownStr<'static> = first_word(MownStr::from(txt));
+    /// assert!(word.is_borrowed());
+    /// assert_eq!(word, "hello");
+    /// ```
+    #[must_use]
+    pub fn into_slice(self, range: impl RangeBounds<usize>) -> MownStr<'a> {
+        let range = to_range_or_panic(range, self.len());
+        let sub = &self[range.clone()];
+        if sub.len() == self.len() {
+            self
+        } else if self.is_borrowed() {
+            // SAFETY: self is borrowed
+            let txt = unsafe { self.make_ref() };
+            MownStr::from_ref(&txt[range])
+        } else if matches!(self.tag(), BOXED | STRING) {
+            let mut txt = String::from(self);
+            txt.truncate(range.end);
+            txt.drain(..range.start);
+            txt.into()
+        } else if self.is_shared() && sub.len() > INLINE_CAP {
+            self.into_shared_slice(range)
+        } else {
+            MownStr::owned(sub)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::INLINE;
+    use super::*;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn slice() {
+        let mown: MownStr = "hello world".to_string().into();
+        let sub = mown.slice(6..);
+        assert!(sub.is_borrowed());
+        assert_eq!(sub, "world");
+        assert_eq!(sub.as_ptr(), mown.as_ptr().wrapping_add(6));
+        assert_eq!(mown.slice(..5), "hello");
+        assert_eq!(mown.slice(..=4), "hello");
+        assert_eq!(mown.slice(2..4), "ll");
+        assert_eq!(mown.slice(..), "hello world");
+        assert_eq!(mown.slice((Bound::Excluded(5), Bound::Unbounded)), "world");
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_out_of_bounds() {
+        let mown = MownStr::from("hello");
+        let _ = mown.slice(2..6);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_not_char_boundary() {
+        let mown = MownStr::from("été");
+        let _ = mown.slice(1..);
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_overflow() {
+        let mown = MownStr::from("hello");
+        let _ = mown.slice(..=usize::MAX);
+    }
+
+    #[test]
+    fn get_mown() {
+        let mown = MownStr::from("été");
+        assert_eq!(mown.get_mown(..2).as_deref(), Some("é"));
+        assert_eq!(mown.get_mown(2..=2).as_deref(), Some("t"));
+        assert_eq!(mown.get_mown(1..), None);
+        assert_eq!(mown.get_mown(..6), None);
+        assert_eq!(
+            mown.get_mown((Bound::Included(3), Bound::Excluded(2))),
+            None
+        );
+        assert_eq!(mown.get_mown(..=usize::MAX), None);
+        assert!(mown.get_mown(..).unwrap().is_borrowed());
+        // str::get is not shadowed
+        let sub: &str = mown.get(..2).unwrap();
+        assert_eq!(sub, "é");
+    }
+
+    #[test]
+    fn split_at_mown() {
+        let mown: MownStr = "hello world".to_string().into();
+        let (left, right) = mown.split_at_mown(5);
+        assert!(left.is_borrowed());
+        assert!(right.is_borrowed());
+        assert_eq!(left, "hello");
+        assert_eq!(right, " world");
+        // str::split_at is not shadowed
+        let (left, _): (&str, &str) = mown.split_at(5);
+        assert_eq!(left, "hello");
+    }
+
+    #[test]
+    fn into_slice_borrowed() {
+        let txt = "hello world".to_string();
+        let sub = {
+            let mown = MownStr::from(&txt[..]);
+            mown.into_slice(6..)
+        };
+        assert!(sub.is_borrowed());
+        assert_eq!(sub.as_ptr(), txt.as_ptr().wrapping_add(6));
+        assert_eq!(sub, "world");
+    }
+
+    #[test]
+    fn into_slice_boxed() {
+        let mown: MownStr = "hello world and everyone".to_string().into();
+        let ptr = mown.as_ptr();
+        let sub = mown.into_slice(6..22);
+        assert_eq!(sub.tag(), STRING);
+        assert_eq!(sub.as_ptr(), ptr);
+        assert_eq!(sub, "world and everyo");
+        assert_eq!(sub.clone().into_slice(..), "world and everyo");
+
+        let mown: MownStr = "hello world and everyone".to_string().into();
+        let sub = mown.into_slice(..5);
+        assert_eq!(sub.tag(), INLINE);
+        assert_eq!(sub, "hello");
+
+        let mown: MownStr = "hello world and everyone".to_string().into();
+        let ptr = mown.as_ptr();
+        let sub = mown.into_slice(..);
+        assert_eq!(sub.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn into_slice_inline() {
+        let mown: MownStr = "hello".to_string().into();
+        let sub = mown.into_slice(1..4);
+        assert_eq!(sub.tag(), INLINE);
+        assert_eq!(sub, "ell");
+    }
+
+    #[test]
+    fn into_slice_shared() {
+        let arc: Arc<str> = "hello world and everyone".into();
+        let mown: MownStr = arc.clone().into();
+        let sub = mown.into_slice(..);
+        assert!(sub.is_shared());
+        let sub = sub.into_slice(6..);
+        assert!(sub.is_shared());
+        assert_eq!(sub, "world and everyone");
+        assert_eq!(sub.as_ptr(), arc.as_ptr().wrapping_add(6));
+        assert_eq!(Arc::strong_count(&arc), 2);
+        let sub2 = sub.clone().into_slice(..5);
+        assert_eq!(sub2.tag(), INLINE);
+        let sub = sub.into_slice(..15);
+        assert!(sub.is_shared());
+        assert_eq!(sub.as_ptr(), arc.as_ptr().wrapping_add(6));
+        assert_eq!(Arc::strong_count(&arc), 2);
+        drop(sub);
+        assert_eq!(Arc::strong_count(&arc), 1);
+        assert_eq!(sub2, "world");
+    }
+
+    #[test]
+    #[should_panic]
+    fn into_slice_not_char_boundary() {
+        let mown: MownStr = "été".to_string().into();
+        let _ = mown.into_slice(..1);
+    }
+}
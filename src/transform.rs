@@ -0,0 +1,361 @@
+//! Transformations of [`MownStr`] that copy the underlying string only when necessary.
+
+use super::split::Pattern;
+use super::MownStr;
+use alloc::borrow::Cow;
+use alloc::string::String;
+use core::ops::Range;
+
+impl<'a> MownStr<'a> {
+    /// The range of `sub` in this `MownStr`,
+    /// or `None` if `sub` is not a sub-slice of it.
+    fn range_of(&self, sub: &str) -> Option<Range<usize>> {
+        let start = (sub.as_ptr() as usize).checked_sub(self.as_ptr() as usize)?;
+        let end = start + sub.len();
+        (end <= self.len()).then_some(start..end)
+    }
+
+    /// Apply `f` to this `MownStr`, where `f` returns a sub-slice of its argument.
+    fn map_slice(self, f: impl FnOnce(&str) -> &str) -> MownStr<'a> {
+        let range = self.range_of(f(&self)).unwrap();
+        self.into_slice(range)
+    }
+
+    /// Apply `f` to this `MownStr`, keeping it unchanged if `f` returns `None`.
+    fn map_slice_or_self(
+        self,
+        f: impl FnOnce(&str) -> Option<&str>,
+    ) -> Result<MownStr<'a>, MownStr<'a>> {
+        match f(&self).map(|sub| self.range_of(sub).unwrap()) {
+            Some(range) => Ok(self.into_slice(range)),
+            None => Err(self),
+        }
+    }
+
+    /// Remove leading and trailing whitespaces, as [`str::trim`] does.
+    ///
+    /// A borrowed `MownStr` remains borrowed (from the same source),
+    /// while an owned `MownStr` is shrunk or copied (only if it actually changes),
+    /// as with [`into_slice`](MownStr::into_slice).
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let txt = "  hello  ".to_string();
+    /// let ms = MownStr::from(&txt).trim_mown();
+    /// assert!(ms.is_borrowed());
+    /// assert_eq!(ms, "hello");
+    /// ```
+    #[must_use]
+    pub fn trim_mown(self) -> MownStr<'a> {
+        self.map_slice(str::trim)
+    }
+
+    /// Remove leading whitespaces, as [`str::trim_start`] does.
+    ///
+    /// See [`trim_mown`](MownStr::trim_mown) for more details.
+    #[must_use]
+    pub fn trim_start_mown(self) -> MownStr<'a> {
+        self.map_slice(str::trim_start)
+    }
+
+    /// Remove trailing whitespaces, as [`str::trim_end`] does.
+    ///
+    /// See [`trim_mown`](MownStr::trim_mown) for more details.
+    #[must_use]
+    pub fn trim_end_mown(self) -> MownStr<'a> {
+        self.map_slice(str::trim_end)
+    }
+
+    /// Remove `prefix` from this `MownStr`, as [`str::strip_prefix`] does.
+    ///
+    /// If this `MownStr` does not start with `prefix`, it is returned unchanged as an error.
+    /// Otherwise, see [`trim_mown`](MownStr::trim_mown) for more details.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let ms = MownStr::from("http://example.org/");
+    /// let ms = ms.strip_prefix_mown("http://").unwrap_or_else(|ms| ms);
+    /// assert!(ms.is_borrowed());
+    /// assert_eq!(ms, "example.org/");
+    /// ```
+    pub fn strip_prefix_mown<P: Pattern>(self, prefix: P) -> Result<MownStr<'a>, MownStr<'a>> {
+        self.map_slice_or_self(|txt| prefix.strip_prefix(txt))
+    }
+
+    /// Remove `suffix` from this `MownStr`, as [`str::strip_suffix`] does.
+    ///
+    /// See [`strip_prefix_mown`](MownStr::strip_prefix_mown) for more details.
+    pub fn strip_suffix_mown<P: Pattern>(self, suffix: P) -> Result<MownStr<'a>, MownStr<'a>> {
+        self.map_slice_or_self(|txt| suffix.strip_suffix(txt))
+    }
+
+    /// Replace all matches of `from` by `to`, as [`str::replace`] does.
+    ///
+    /// If there is no match, this `MownStr` is returned unchanged, without any copy.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let ms = MownStr::from("hello world").replace_mown('_', " ");
+    /// assert!(ms.is_borrowed());
+    /// let ms = ms.replace_mown(' ', "_");
+    /// assert!(ms.is_owned());
+    /// assert_eq!(ms, "hello_world");
+    /// ```
+    #[must_use]
+    pub fn replace_mown<P: Pattern>(self, from: P, to: &str) -> MownStr<'a> {
+        let replaced = {
+            let mut pieces = from.split(&self);
+            let first = pieces.next().unwrap_or_default();
+            pieces.next().map(|second| {
+                let mut ret = String::with_capacity(self.len());
+                ret.push_str(first);
+                ret.push_str(to);
+                ret.push_str(second);
+                for piece in pieces {
+                    ret.push_str(to);
+                    ret.push_str(piece);
+                }
+                ret
+            })
+        };
+        match replaced {
+            Some(ret) => ret.into(),
+            None => self,
+        }
+    }
+
+    /// Convert this `MownStr` to lowercase, as [`str::to_lowercase`] does.
+    ///
+    /// If it is already lowercase, this `MownStr` is returned unchanged, without any copy.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let ms = MownStr::from("hello").to_lowercase_mown();
+    /// assert!(ms.is_borrowed());
+    /// let ms = MownStr::from("HÉLLO").to_lowercase_mown();
+    /// assert!(ms.is_owned());
+    /// assert_eq!(ms, "héllo");
+    /// ```
+    #[must_use]
+    pub fn to_lowercase_mown(self) -> MownStr<'a> {
+        // NB: the only context-dependant mapping of str::to_lowercase
+        // is for 'Σ', which is changed anyway
+        if self.chars().all(|c| c.to_lowercase().eq([c])) {
+            self
+        } else {
+            self.to_lowercase().into()
+        }
+    }
+
+    /// Convert this `MownStr` to uppercase, as [`str::to_uppercase`] does.
+    ///
+    /// If it is already uppercase, this `MownStr` is returned unchanged, without any copy.
+    #[must_use]
+    pub fn to_uppercase_mown(self) -> MownStr<'a> {
+        if self.chars().all(|c| c.to_uppercase().eq([c])) {
+            self
+        } else {
+            self.to_uppercase().into()
+        }
+    }
+
+    /// Convert this `MownStr` to ASCII lowercase, as [`str::to_ascii_lowercase`] does.
+    ///
+    /// If it is already lowercase, this `MownStr` is returned unchanged, without any copy.
+    /// Otherwise, an exclusively owned `MownStr` is modified in place.
+    #[must_use]
+    pub fn to_ascii_lowercase_mown(mut self) -> MownStr<'a> {
+        if self.bytes().any(|b| b.is_ascii_uppercase()) {
+            self.make_mut().make_ascii_lowercase();
+        }
+        self
+    }
+
+    /// Convert this `MownStr` to ASCII uppercase, as [`str::to_ascii_uppercase`] does.
+    ///
+    /// If it is already uppercase, this `MownStr` is returned unchanged, without any copy.
+    /// Otherwise, an exclusively owned `MownStr` is modified in place.
+    #[must_use]
+    pub fn to_ascii_uppercase_mown(mut self) -> MownStr<'a> {
+        if self.bytes().any(|b| b.is_ascii_lowercase()) {
+            self.make_mut().make_ascii_uppercase();
+        }
+        self
+    }
+
+    /// Apply `f` to this `MownStr`.
+    ///
+    /// If `f` returns a `Cow::Borrowed` sub-slice of its argument,
+    /// the result is that sub-slice (see [`into_slice`](MownStr::into_slice));
+    /// if it returns any other `Cow::Borrowed`, it is copied.
+    /// If `f` returns a `Cow::Owned`, the `String` is moved into the result.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// # use std::borrow::Cow;
+    /// fn unquote(txt: &str) -> Cow<str> {
+    ///     let txt = txt.trim_matches('"');
+    ///     if txt.contains("\\\"") {
+    ///         Cow::Owned(txt.replace("\\\"", "\""))
+    ///     } else {
+    ///         Cow::Borrowed(txt)
+    ///     }
+    /// }
+    /// let ms = MownStr::from("\"hello\"").map_cow(unquote);
+    /// assert!(ms.is_borrowed());
+    /// assert_eq!(ms, "hello");
+    /// ```
+    #[must_use]
+    pub fn map_cow<F>(self, f: F) -> MownStr<'a>
+    where
+        F: for<'x> FnOnce(&'x str) -> Cow<'x, str>,
+    {
+        let range = match f(&self) {
+            Cow::Owned(txt) => return txt.into(),
+            Cow::Borrowed(sub) => match self.range_of(sub) {
+                Some(range) => range,
+                None => return MownStr::owned(sub),
+            },
+        };
+        self.into_slice(range)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::{BOXED, INLINE};
+    use super::*;
+    use alloc::string::ToString;
+
+    const LONG: &str = "  hello world and everyone  ";
+
+    #[test]
+    fn trim_borrowed() {
+        let txt = LONG.to_string();
+        let mown = MownStr::from(&txt).trim_mown();
+        assert!(mown.is_borrowed());
+        assert_eq!(mown.as_ptr(), txt.as_ptr().wrapping_add(2));
+        assert_eq!(mown, "hello world and everyone");
+        assert_eq!(MownStr::from(&txt).trim_start_mown(), &LONG[2..]);
+        assert_eq!(MownStr::from(&txt).trim_end_mown(), &LONG[..26]);
+    }
+
+    #[test]
+    fn trim_owned() {
+        let mown = MownStr::from(LONG.to_string()).trim_mown();
+        assert_eq!(mown.tag(), BOXED);
+        assert_eq!(mown, "hello world and everyone");
+
+        let mown = MownStr::from("hello world and everyone".to_string());
+        let ptr = mown.as_ptr();
+        let mown = mown.trim_mown();
+        assert_eq!(mown.as_ptr(), ptr);
+
+        let mown = MownStr::from("  hello ".to_string()).trim_mown();
+        assert_eq!(mown.tag(), INLINE);
+        assert_eq!(mown, "hello");
+    }
+
+    #[test]
+    fn trim_empty() {
+        let mown = MownStr::from("   ".to_string()).trim_mown();
+        assert_eq!(mown, "");
+        let mown = MownStr::from("").trim_mown();
+        assert_eq!(mown, "");
+    }
+
+    #[test]
+    fn strip() {
+        let txt = "http://example.org/";
+        let mown = MownStr::from(txt).strip_prefix_mown("http://").unwrap();
+        assert!(mown.is_borrowed());
+        assert_eq!(mown.as_ptr(), txt.as_ptr().wrapping_add(7));
+        let mown = mown.strip_suffix_mown('/').unwrap();
+        assert_eq!(mown, "example.org");
+        let mown = mown.strip_prefix_mown("https://").unwrap_err();
+        assert_eq!(mown, "example.org");
+        let mown = mown.strip_suffix_mown(['a', 'g']).unwrap();
+        assert_eq!(mown, "example.or");
+
+        let mown = MownStr::from(txt.to_string());
+        let mown = mown.strip_suffix_mown("/").unwrap();
+        assert!(mown.is_owned());
+        assert_eq!(mown, "http://example.org");
+    }
+
+    #[test]
+    fn replace() {
+        let txt = "a,b,c";
+        let mown = MownStr::from(txt).replace_mown(';', ", ");
+        assert!(mown.is_borrowed());
+        assert_eq!(mown.as_ptr(), txt.as_ptr());
+        let mown = mown.replace_mown(',', ", ");
+        assert!(mown.is_owned());
+        assert_eq!(mown, "a, b, c");
+        let mown = mown.replace_mown(", ", "");
+        assert_eq!(mown, "abc");
+        for (pat, to) in [("", "-"), ("abc", ""), ("a", "aa"), ("c", "")] {
+            assert_eq!(
+                MownStr::from("abc").replace_mown(pat, to),
+                "abc".replace(pat, to)
+            );
+        }
+        assert_eq!(MownStr::from("").replace_mown("", "x"), "x");
+    }
+
+    #[test]
+    fn case() {
+        let mown = MownStr::from("hello world").to_lowercase_mown();
+        assert!(mown.is_borrowed());
+        let mown = mown.to_uppercase_mown();
+        assert!(mown.is_owned());
+        assert_eq!(mown, "HELLO WORLD");
+        let mown = MownStr::from("straße").to_uppercase_mown();
+        assert_eq!(mown, "STRASSE");
+        let mown = MownStr::from("ΟΔΟΣ").to_lowercase_mown();
+        assert_eq!(mown, "οδος");
+        let mown = MownStr::from("HELLO").to_uppercase_mown();
+        assert!(mown.is_borrowed());
+    }
+
+    #[test]
+    fn ascii_case() {
+        let mown = MownStr::from("HELLO").to_ascii_uppercase_mown();
+        assert!(mown.is_borrowed());
+        let mown = mown.to_ascii_lowercase_mown();
+        assert!(mown.is_owned());
+        assert_eq!(mown, "hello");
+
+        let mown = MownStr::from("hello world and everyone".to_string());
+        let ptr = mown.as_ptr();
+        let mown = mown.to_ascii_uppercase_mown();
+        assert_eq!(mown.as_ptr(), ptr);
+        assert_eq!(mown, "HELLO WORLD AND EVERYONE");
+        assert_eq!(MownStr::from("été").to_ascii_uppercase_mown(), "éTé");
+    }
+
+    #[test]
+    fn map_cow() {
+        let txt = LONG.to_string();
+        let mown = MownStr::from(&txt).map_cow(|t| Cow::Borrowed(t.trim()));
+        assert!(mown.is_borrowed());
+        assert_eq!(mown.as_ptr(), txt.as_ptr().wrapping_add(2));
+
+        let mown = MownStr::from(&txt).map_cow(|_| Cow::Borrowed("static"));
+        assert!(mown.is_owned());
+        assert_eq!(mown, "static");
+
+        let mown = MownStr::from(&txt).map_cow(|t| Cow::Owned(t.replace(' ', "")));
+        assert!(mown.is_owned());
+        assert_eq!(mown, "helloworldandeveryone");
+
+        let mown = MownStr::from(LONG.to_string()).map_cow(|t| Cow::Borrowed(&t[2..7]));
+        assert_eq!(mown.tag(), INLINE);
+        assert_eq!(mown, "hello");
+    }
+}
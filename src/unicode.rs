@@ -0,0 +1,288 @@
+//! Unicode normalization of [`MownStr`].
+
+use super::MownStr;
+use alloc::borrow::Borrow;
+use alloc::boxed::Box;
+use alloc::string::String;
+use core::fmt;
+use core::ops::Deref;
+use core::str::Chars;
+use unicode_normalization::{
+    is_nfc_quick, is_nfd_quick, is_nfkc_quick, is_nfkd_quick, IsNormalized, UnicodeNormalization,
+};
+
+/// Normalize `mown` with `normalize`, unless `quick_check` says it is already normalized.
+fn normalize<'a>(
+    mown: MownStr<'a>,
+    quick_check: fn(Chars) -> IsNormalized,
+    normalize: fn(Chars) -> String,
+) -> MownStr<'a> {
+    match quick_check(mown.chars()) {
+        IsNormalized::Yes => mown,
+        IsNormalized::No => normalize(mown.chars()).into(),
+        IsNormalized::Maybe => {
+            let normalized = normalize(mown.chars());
+            if *normalized == *mown {
+                mown
+            } else {
+                normalized.into()
+            }
+        }
+    }
+}
+
+impl<'a> MownStr<'a> {
+    /// Convert this `MownStr` to Unicode Normalization Form C (canonical composition).
+    ///
+    /// If this `MownStr` is already normalized, it is returned unchanged
+    /// (and, in particular, stays borrowed if it was);
+    /// otherwise, the normalized string is allocated.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let ms = MownStr::from("caf\u{e9}").nfc();
+    /// assert!(ms.is_borrowed());
+    /// let ms = MownStr::from("cafe\u{301}").nfc();
+    /// assert!(ms.is_owned());
+    /// assert_eq!(ms, "caf\u{e9}");
+    /// ```
+    #[must_use]
+    pub fn nfc(self) -> MownStr<'a> {
+        normalize(
+            self,
+            |chars| is_nfc_quick(chars),
+            |chars| chars.nfc().collect(),
+        )
+    }
+
+    /// Convert this `MownStr` to Unicode Normalization Form KC (compatibility composition).
+    ///
+    /// See [`nfc`](MownStr::nfc).
+    #[must_use]
+    pub fn nfkc(self) -> MownStr<'a> {
+        normalize(
+            self,
+            |chars| is_nfkc_quick(chars),
+            |chars| chars.nfkc().collect(),
+        )
+    }
+
+    /// Convert this `MownStr` to Unicode Normalization Form D (canonical decomposition).
+    ///
+    /// See [`nfc`](MownStr::nfc).
+    #[must_use]
+    pub fn nfd(self) -> MownStr<'a> {
+        normalize(
+            self,
+            |chars| is_nfd_quick(chars),
+            |chars| chars.nfd().collect(),
+        )
+    }
+
+    /// Convert this `MownStr` to Unicode Normalization Form KD (compatibility decomposition).
+    ///
+    /// See [`nfc`](MownStr::nfc).
+    #[must_use]
+    pub fn nfkd(self) -> MownStr<'a> {
+        normalize(
+            self,
+            |chars| is_nfkd_quick(chars),
+            |chars| chars.nfkd().collect(),
+        )
+    }
+}
+
+/// A [`MownStr`] in Unicode Normalization Form C.
+///
+/// As canonically equivalent strings have the same NFC,
+/// comparing and hashing `NormalizedMownStr`s is as cheap as comparing and hashing `str`s,
+/// but takes canonical equivalence into account.
+///
+/// # Usage
+/// ```
+/// # use mownstr::NormalizedMownStr;
+/// let composed = NormalizedMownStr::new("caf\u{e9}");
+/// let decomposed = NormalizedMownStr::new("cafe\u{301}");
+/// assert_eq!(composed, decomposed);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NormalizedMownStr<'a>(MownStr<'a>);
+
+impl<'a> NormalizedMownStr<'a> {
+    /// Build a `NormalizedMownStr`, normalizing `mown` if necessary.
+    ///
+    /// See [`MownStr::nfc`].
+    #[must_use]
+    pub fn new(mown: impl Into<MownStr<'a>>) -> Self {
+        NormalizedMownStr(mown.into().nfc())
+    }
+
+    #[must_use]
+    pub fn as_mown(&self) -> &MownStr<'a> {
+        &self.0
+    }
+
+    #[must_use]
+    pub fn into_inner(self) -> MownStr<'a> {
+        self.0
+    }
+
+    #[must_use]
+    pub fn borrowed(&self) -> NormalizedMownStr<'_> {
+        NormalizedMownStr(self.0.borrowed())
+    }
+
+    /// Detach this `NormalizedMownStr` from its lifetime.
+    ///
+    /// See [`MownStr::into_owned`].
+    #[must_use]
+    pub fn into_owned(self) -> NormalizedMownStr<'static> {
+        NormalizedMownStr(self.0.into_owned())
+    }
+}
+
+impl<'a> From<MownStr<'a>> for NormalizedMownStr<'a> {
+    fn from(other: MownStr<'a>) -> Self {
+        Self::new(other)
+    }
+}
+
+impl<'a> From<&'a str> for NormalizedMownStr<'a> {
+    fn from(other: &'a str) -> Self {
+        Self::new(other)
+    }
+}
+
+impl From<Box<str>> for NormalizedMownStr<'_> {
+    fn from(other: Box<str>) -> Self {
+        Self::new(other)
+    }
+}
+
+impl From<String> for NormalizedMownStr<'_> {
+    fn from(other: String) -> Self {
+        Self::new(other)
+    }
+}
+
+impl<'a> From<NormalizedMownStr<'a>> for MownStr<'a> {
+    fn from(other: NormalizedMownStr<'a>) -> Self {
+        other.0
+    }
+}
+
+// Using a NormalizedMownStr as a str
+
+impl Deref for NormalizedMownStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for NormalizedMownStr<'_> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for NormalizedMownStr<'_> {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for NormalizedMownStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use std::collections::HashSet;
+
+    const COMPOSED: &str = "caf\u{e9}";
+    const DECOMPOSED: &str = "cafe\u{301}";
+
+    #[test]
+    fn nfc() {
+        let mown = MownStr::from(COMPOSED).nfc();
+        assert!(mown.is_borrowed());
+        let mown = MownStr::from(DECOMPOSED).nfc();
+        assert!(mown.is_owned());
+        assert_eq!(mown, COMPOSED);
+        let mown = MownStr::from("hello").nfc();
+        assert!(mown.is_borrowed());
+    }
+
+    #[test]
+    fn nfd() {
+        let mown = MownStr::from(DECOMPOSED).nfd();
+        assert!(mown.is_borrowed());
+        let mown = MownStr::from(COMPOSED).nfd();
+        assert!(mown.is_owned());
+        assert_eq!(mown, DECOMPOSED);
+    }
+
+    #[test]
+    fn nfkc_nfkd() {
+        let mown = MownStr::from("\u{fb01}").nfkc();
+        assert_eq!(mown, "fi");
+        let mown = MownStr::from("\u{fb01}").nfc();
+        assert!(mown.is_borrowed());
+        let mown = MownStr::from(COMPOSED).nfkd();
+        assert_eq!(mown, DECOMPOSED);
+        let mown = MownStr::from(DECOMPOSED).nfkd();
+        assert!(mown.is_borrowed());
+    }
+
+    #[test]
+    fn nfc_maybe() {
+        // U+0301 alone is "maybe" NFC, but is actually normalized
+        let mown = MownStr::from("\u{301}").nfc();
+        assert!(mown.is_borrowed());
+        // e followed by U+0301 is "maybe" NFC, but is not normalized
+        let mown = MownStr::from("abce\u{301}").nfc();
+        assert_eq!(mown, "abc\u{e9}");
+    }
+
+    #[test]
+    fn nfc_keeps_owned() {
+        let mown = MownStr::from(format!("{COMPOSED} is a French word"));
+        let ptr = mown.as_ptr();
+        let mown = mown.nfc();
+        assert_eq!(mown.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn normalized_eq() {
+        let n1 = NormalizedMownStr::new(COMPOSED);
+        let n2 = NormalizedMownStr::from(DECOMPOSED.to_string());
+        assert!(n1.as_mown().is_borrowed());
+        assert_eq!(n1, n2);
+        assert_eq!(n1.cmp(&n2), core::cmp::Ordering::Equal);
+        assert!(NormalizedMownStr::new("cafe") < n1);
+        assert_eq!(&*n2, COMPOSED);
+    }
+
+    #[test]
+    fn normalized_hash() {
+        let mut set = HashSet::new();
+        set.insert(NormalizedMownStr::new(DECOMPOSED));
+        assert!(set.contains(&NormalizedMownStr::new(COMPOSED)));
+        assert!(set.contains(COMPOSED));
+    }
+
+    #[test]
+    fn normalized_into() {
+        let n = NormalizedMownStr::new(DECOMPOSED).into_owned();
+        assert_eq!(n.borrowed(), n);
+        assert_eq!(n.to_string(), COMPOSED);
+        assert_eq!(MownStr::from(n), COMPOSED);
+    }
+}
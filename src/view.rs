@@ -0,0 +1,157 @@
+//! Explicit view on the ownership of a [`MownStr`].
+
+use super::{MownStr, BOXED, INLINE, STRING};
+use alloc::boxed::Box;
+
+/// The content of a [`MownStr`], either borrowed or owned,
+/// as returned by [`MownStr::into_view`].
+///
+/// # Usage
+/// ```
+/// # use mownstr::{MownStr, MownView};
+/// fn describe(ms: MownStr) -> &'static str {
+///     match ms.into_view() {
+///         MownView::Borrowed(_) => "borrowed",
+///         MownView::Owned(_) => "owned",
+///     }
+/// }
+/// assert_eq!(describe(MownStr::from("hello")), "borrowed");
+/// assert_eq!(describe(MownStr::from("hello".to_string())), "owned");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum MownView<'a> {
+    Borrowed(&'a str),
+    Owned(Box<str>),
+}
+
+impl<'a> MownStr<'a> {
+    /// Convert this `MownStr` into a [`MownView`].
+    ///
+    /// An owned `Box<str>` is moved without any copy,
+    /// while inline and shared strings are copied into a new `Box<str>`.
+    #[must_use]
+    pub fn into_view(self) -> MownView<'a> {
+        match self.try_into_box() {
+            Ok(bx) => MownView::Owned(bx),
+            Err(this) => match this.as_borrowed() {
+                Some(txt) => MownView::Borrowed(txt),
+                None => MownView::Owned(Box::from(&*this)),
+            },
+        }
+    }
+
+    /// The underlying `&'a str`, if this `MownStr` is borrowed.
+    ///
+    /// # Usage
+    /// ```
+    /// # use mownstr::MownStr;
+    /// let txt = "hello".to_string();
+    /// let ms = MownStr::from(&txt);
+    /// let r: Option<&str> = ms.as_borrowed();
+    /// drop(ms);
+    /// assert_eq!(r, Some("hello"));
+    /// ```
+    #[must_use]
+    pub fn as_borrowed(&self) -> Option<&'a str> {
+        // SAFETY: self is borrowed
+        self.is_borrowed().then(|| unsafe { self.make_ref() })
+    }
+
+    /// Convert this `MownStr` into a `Box<str>`, if it exclusively owns its data.
+    ///
+    /// An owned `Box<str>` is moved without any copy,
+    /// an owned `String` is shrunk to fit (which may reallocate it),
+    /// and inline strings, being short, are copied into a new `Box<str>`.
+    /// Borrowed and [shared](MownStr::is_shared) `MownStr`s are returned unchanged as an error.
+    pub fn try_into_box(mut self) -> Result<Box<str>, Self> {
+        match self.tag() {
+            // SAFETY: self is boxed
+            BOXED => Ok(unsafe { self.extract_box() }),
+            // SAFETY: self is a string
+            STRING => Ok(unsafe { self.extract_string() }.into_boxed_str()),
+            INLINE => Ok(Box::from(&*self)),
+            _ => Err(self),
+        }
+    }
+}
+
+impl<'a> From<MownView<'a>> for MownStr<'a> {
+    fn from(other: MownView<'a>) -> Self {
+        match other {
+            MownView::Borrowed(txt) => txt.into(),
+            MownView::Owned(bx) => bx.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::sync::Arc;
+
+    #[test]
+    fn view_borrowed() {
+        let txt = "hello".to_string();
+        let view = MownStr::from(&txt).into_view();
+        assert_eq!(view, MownView::Borrowed(&txt));
+        let mown = MownStr::from(view);
+        assert!(mown.is_borrowed());
+        assert_eq!(mown.as_ptr(), txt.as_ptr());
+    }
+
+    #[test]
+    fn view_boxed() {
+        let mown = MownStr::from("hello world and everyone".to_string());
+        let ptr = mown.as_ptr();
+        let MownView::Owned(bx) = mown.into_view() else {
+            panic!("expected owned view")
+        };
+        assert_eq!(bx.as_ptr(), ptr);
+        let mown = MownStr::from(MownView::Owned(bx));
+        assert_eq!(mown.tag(), BOXED);
+        assert_eq!(mown.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn view_inline() {
+        let view = MownStr::from("hello".to_string()).into_view();
+        assert_eq!(view, MownView::Owned("hello".into()));
+    }
+
+    #[test]
+    fn view_shared() {
+        let arc: Arc<str> = "hello".into();
+        let view = MownStr::from(arc.clone()).into_view();
+        assert_eq!(view, MownView::Owned("hello".into()));
+        assert_eq!(Arc::strong_count(&arc), 1);
+    }
+
+    #[test]
+    fn as_borrowed() {
+        let txt = "hello".to_string();
+        assert_eq!(MownStr::from(&txt).as_borrowed(), Some("hello"));
+        assert_eq!(MownStr::from(txt.clone()).as_borrowed(), None);
+        assert_eq!(MownStr::from(Arc::<str>::from(txt)).as_borrowed(), None);
+    }
+
+    #[test]
+    fn try_into_box() {
+        let mown = MownStr::from("hello world and everyone".to_string());
+        let ptr = mown.as_ptr();
+        let bx = mown.try_into_box().unwrap();
+        assert_eq!(bx.as_ptr(), ptr);
+
+        let mown = MownStr::from("hello".to_string());
+        assert_eq!(mown.try_into_box().as_deref(), Ok("hello"));
+
+        let mown = MownStr::from("hello");
+        let mown = mown.try_into_box().unwrap_err();
+        assert!(mown.is_borrowed());
+
+        let arc: Arc<str> = "hello".into();
+        let mown = MownStr::from(arc.clone()).try_into_box().unwrap_err();
+        assert!(mown.is_shared());
+        assert_eq!(Arc::strong_count(&arc), 2);
+    }
+}